@@ -9,6 +9,7 @@ pub enum ProposalStatus {
     Defeated,
     Succeeded,
     Executed,
+    Vetoed,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -18,6 +19,159 @@ pub enum VoteType {
     Abstain,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum VotingMode {
+    Linear,    // Weight equals Membership.voting_power
+    Quadratic, // Weight equals the square root of deposited voting tokens
+}
+
+// Entry requirement checked when joining a community and on membership re-checks
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MembershipRequirement {
+    None,
+    CollectionNft { collection: Pubkey },            // Hold an NFT of a verified CollectionRating collection
+    FractionBalance { vault: Pubkey, min_amount: u64 }, // Hold a minimum of a vault's fraction tokens
+    MinReputation { min_score: u64 },                // Minimum ReputationScore.total_score
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum JoinPolicy {
+    Open,         // Anyone meeting the membership requirement can join
+    InviteOnly,   // Joining consumes an invite issued by an admin
+    Application,  // Joining consumes an application approved by an admin
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ApplicationStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum VoteTipping {
+    Disabled, // Always wait for the end of the voting period
+    Strict,   // Finalize early only once the outcome can no longer change
+    Early,    // Also succeed early once quorum is met and For leads Against
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MemberRole {
+    Member,
+    Moderator,
+    Admin,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    CreateProposal,
+    Moderate,
+    ManageMembers,
+    ManageTreasury,
+}
+
+impl MemberRole {
+    // Permission matrix for community roles
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        matches!(
+            (self, permission),
+            (MemberRole::Admin, _)
+                | (MemberRole::Moderator, Permission::CreateProposal | Permission::Moderate)
+                | (MemberRole::Member, Permission::CreateProposal)
+        )
+    }
+    
+    pub fn promoted(&self) -> Option<MemberRole> {
+        match self {
+            MemberRole::Member => Some(MemberRole::Moderator),
+            MemberRole::Moderator => Some(MemberRole::Admin),
+            MemberRole::Admin => None,
+        }
+    }
+    
+    pub fn demoted(&self) -> Option<MemberRole> {
+        match self {
+            MemberRole::Admin => Some(MemberRole::Moderator),
+            MemberRole::Moderator => Some(MemberRole::Member),
+            MemberRole::Member => None,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BallotType {
+    Binary,       // For/Against/Abstain via cast_vote
+    SingleChoice, // One option per ballot
+    Approval,     // Any number of distinct options, each gets the full weight
+    RankedChoice, // Options ranked in order, scored with a Borda count
+}
+
+pub const MAX_PROPOSAL_OPTIONS: usize = 10;
+
+// Content of a new proposal, passed to create_proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateProposalArgs {
+    pub title: String,
+    pub description: String,
+    pub is_investment_proposal: bool,
+    pub target_collection: Option<Pubkey>,
+    pub investment_amount: Option<u64>,
+    pub action: Option<ProposalAction>,
+    pub ballot_type: BallotType,
+    pub options: Vec<String>,
+}
+
+// Oldest collection price, in seconds, accepted when valuing strategy portfolios
+pub const PRICE_FEED_MAX_AGE: i64 = 86_400;
+
+// Oldest rebalance plan, in seconds since it was priced, whose orders can still be filled, long enough for its approval vote
+pub const REBALANCE_PLAN_MAX_AGE: i64 = 7 * 86_400;
+
+// Reputation epochs decayed step by step, points idle for longer are dropped entirely
+pub const MAX_DECAY_EPOCHS: u64 = 104;
+
+// Extra vote weight per reputation point, and its ceiling, under reputation-weighted voting
+pub const REPUTATION_VOTE_BPS_PER_POINT: u64 = 10;
+pub const MAX_REPUTATION_VOTE_BONUS_BPS: u64 = 20_000;
+
+// Reputation defaults for new governance configs
+pub const DEFAULT_REPUTATION_DECAY_BPS: u16 = 500;
+pub const DEFAULT_PARTICIPATION_CAP: u64 = 200;
+pub const DEFAULT_DEFEAT_PENALTY: u64 = 25;
+pub const DEFAULT_VETO_PENALTY: u64 = 100;
+
+// Voting power checkpoint slots allocated per member or faction bloc, accounts grow past them as needed
+pub const INITIAL_POWER_CHECKPOINTS: usize = 16;
+
+// Serialized size of one PowerCheckpoint
+pub const POWER_CHECKPOINT_SIZE: usize = 8 + 8 + 8 + 8 + 1 + 8 + 8 + 1;
+
+// Prefix of the messages members sign for relayed votes
+pub const SIGNED_VOTE_DOMAIN: &[u8] = b"solmosaic:vote";
+
+// Maximum number of communities taking part in a federated proposal
+pub const MAX_FEDERATED_COMMUNITIES: usize = 8;
+
+// Maximum number of curators on a community's council
+pub const MAX_CURATORS: usize = 9;
+
+// Maximum number of admins of a faction, who approve its treasury spends
+pub const MAX_FACTION_ADMINS: usize = 9;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProposalOption {
+    pub label: String,
+    pub vote_weight: u64,
+    pub instruction_count: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FundingStatus {
+    Active,
+    Funded,
+    Canceled,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct ProposalInstruction {
     pub program_id: Pubkey,
@@ -32,6 +186,131 @@ pub struct ProposalAccount {
     pub is_writable: bool,
 }
 
+// Treasury payout approved by a proposal, paid out on execution
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TreasuryTransfer {
+    pub treasury: Pubkey,
+    pub destination: Pubkey, // Token account receiving the funds
+    pub amount: u64,
+}
+
+// Typed operation carried out natively when a proposal is executed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum ProposalAction {
+    // Pay out of a community treasury
+    TreasuryTransfer(TreasuryTransfer),
+    // Buy vault fractions with treasury funds, settled by the seller executing the proposal
+    InvestInVault {
+        vault: Pubkey,
+        treasury: Pubkey,            // Treasury paying for the fractions
+        fraction_treasury: Pubkey,   // Treasury receiving the vault's fraction tokens
+        seller: Pubkey,
+        payment_destination: Pubkey, // Seller's token account receiving the payment
+        price: u64,
+        fraction_amount: u64,
+    },
+    UpdateConfig(GovernanceConfigUpdate),
+    // Verify a collection in a rating system administered by the community
+    VerifyCollection {
+        rating_system: Pubkey,
+        collection: Pubkey,
+    },
+    // Create an investment strategy owned by the community, credited to the proposer
+    AddStrategy {
+        name: String,
+        description: String,
+        risk_level: u8,
+        target_collections: Vec<Pubkey>,
+        allocation_percentages: Vec<u8>,
+    },
+    RemoveMember {
+        membership: Pubkey,
+    },
+    // Curator council membership is maintained through governance
+    AppointCurator {
+        curator: Pubkey,
+    },
+    RemoveCurator {
+        curator: Pubkey,
+    },
+    // Council approvals required on investment proposals (0 = no council approval)
+    SetCouncilThreshold {
+        approval_threshold: u8,
+    },
+    // Move treasury funds into an investment strategy's portfolio
+    FundStrategy {
+        strategy: Pubkey,
+        treasury: Pubkey,
+        amount: u64,
+    },
+    // Replace an investment strategy's target allocations
+    UpdateAllocations {
+        strategy: Pubkey,
+        allocation_percentages: Vec<u8>,
+    },
+    // Approve a computed rebalance plan as a batch of orders
+    ApproveRebalance {
+        plan: Pubkey,
+        computed_at: i64,     // Ties the approval to one computation of the plan
+    },
+    // Approve the community's part in a cross-community federated proposal
+    ApproveFederated {
+        federated_proposal: Pubkey,
+    },
+    // Fund the governance reward pool out of a community treasury
+    FundRewardPool {
+        treasury: Pubkey,
+        amount: u64,
+    },
+}
+
+impl ProposalAction {
+    // Serialized size, used to size the proposal account
+    pub fn space(&self) -> usize {
+        self.try_to_vec().map(|data| data.len()).unwrap_or(0)
+    }
+    
+    // Seed of the strategy account created by an AddStrategy action
+    pub fn strategy_name(&self) -> &str {
+        match self {
+            ProposalAction::AddStrategy { name, .. } => name,
+            _ => "",
+        }
+    }
+    
+    // Space of the strategy account created by an AddStrategy action
+    pub fn strategy_space(&self) -> usize {
+        match self {
+            ProposalAction::AddStrategy { name, description, target_collections, allocation_percentages, .. } => {
+                8 + 32 + 4 + name.len() + 4 + description.len() + 32 + 1 +
+                    4 + (target_collections.len() * 32) + 4 + allocation_percentages.len() + 8 + 8 + 8 + 1
+            },
+            _ => 0,
+        }
+    }
+    
+    // Actions moving community funds, which need the curator council's approval
+    pub fn requires_council(&self) -> bool {
+        matches!(
+            self,
+            ProposalAction::TreasuryTransfer(_)
+                | ProposalAction::InvestInVault { .. }
+                | ProposalAction::FundStrategy { .. }
+                | ProposalAction::ApproveRebalance { .. }
+                | ProposalAction::ApproveFederated { .. }
+                | ProposalAction::FundRewardPool { .. }
+        )
+    }
+    
+    // Seed of the config amendment approved by an UpdateConfig action
+    pub fn config_hash(&self) -> [u8; 32] {
+        match self {
+            ProposalAction::UpdateConfig(update) => update.hash(),
+            _ => [0; 32],
+        }
+    }
+}
+
 // Main Governance account to track communities
 #[account]
 pub struct GovernanceConfig {
@@ -42,6 +321,59 @@ pub struct GovernanceConfig {
     pub proposal_count: u64,
     pub total_communities: u64,
     pub is_active: bool,
+    pub execution_delay: i64,    // Hold-up in seconds between success and earliest execution
+    pub execution_window: i64,   // Seconds a succeeded proposal stays executable after the delay (0 = no expiry)
+    pub reward_epoch_duration: i64, // Length of an activity reward epoch in seconds
+    pub reputation_decay_bps: u16,  // Share of participation points lost per epoch
+    pub participation_cap: u64,     // Participation points a user can earn per epoch (0 = uncapped)
+    pub defeat_penalty: u64,        // Participation points a proposer loses on defeat
+    pub veto_penalty: u64,          // Participation points a proposer loses on veto
+    pub reputation_voting: bool,    // Reputation multiplies voting power on new proposals
+    pub config_version: u64,        // Bumped whenever an amendment is applied
+    pub recognized_communities: u64, // Active communities recognized by the authority, the base of amendment quorums
+}
+
+impl GovernanceConfig {
+    pub fn epoch_at(&self, timestamp: i64) -> u64 {
+        (timestamp / self.reward_epoch_duration) as u64
+    }
+}
+
+// Parameters amended once a majority of recognized communities approved them, None leaves a value unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct GovernanceConfigUpdate {
+    pub min_vote_threshold: Option<u64>,
+    pub voting_period: Option<i64>,
+    pub is_active: Option<bool>,
+    pub reputation_decay_bps: Option<u16>,
+    pub participation_cap: Option<u64>,
+    pub defeat_penalty: Option<u64>,
+    pub veto_penalty: Option<u64>,
+    pub reputation_voting: Option<bool>,
+}
+
+impl GovernanceConfigUpdate {
+    // Communities approving identical updates approve the same amendment
+    pub fn hash(&self) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hash(&self.try_to_vec().unwrap_or_default()).to_bytes()
+    }
+}
+
+// Config update collecting approvals from communities' executed proposals
+#[account]
+pub struct ConfigAmendment {
+    pub governance: Pubkey,
+    pub config_version: u64,        // Approvals only count against the config they were given for
+    pub update: GovernanceConfigUpdate,
+    pub approvals: u32,
+    pub created_at: i64,
+}
+
+// Marks a community's approval of a config amendment
+#[account]
+pub struct ConfigAmendmentApproval {
+    pub amendment: Pubkey,
+    pub community: Pubkey,
 }
 
 // Community group that makes decisions
@@ -55,6 +387,31 @@ pub struct Community {
     pub proposal_count: u64,
     pub creation_time: i64,
     pub is_active: bool,
+    pub veto_window: i64,      // Seconds after voting ends during which admins/moderators can still veto
+    pub voting_mode: VotingMode,
+    pub voting_mint: Option<Pubkey>, // Token deposited by members for quadratic voting
+    pub reveal_period: i64,    // Reveal phase for secret ballots in seconds (0 = open ballots)
+    pub max_escrow_lock: i64,  // Longest vote-escrow lock in seconds, earning full escrow power
+    pub vote_tipping: VoteTipping,
+    pub total_voting_power: u64,   // Sum of active members' voting power
+    pub total_escrow_weight: u64,  // Upper bound of vote-escrow power, in whole tokens
+    pub proposal_deposit: u64,     // Locked at proposal creation (0 = no deposit)
+    pub deposit_mint: Option<Pubkey>, // Token of the deposit, None for SOL
+    pub membership_requirement: MembershipRequirement,
+    pub join_policy: JoinPolicy,
+    pub council_threshold: u8,     // Curator approvals required on investment proposals (0 = none)
+    pub recognized: bool,          // Counted towards amendment quorums, granted by the governance authority
+}
+
+// Community treasury holding one token mint, owned by a PDA
+#[account]
+pub struct CommunityTreasury {
+    pub community: Pubkey,
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub total_contributed: u64,
+    pub total_spent: u64,
+    pub bump: u8,
 }
 
 // Community membership
@@ -62,11 +419,55 @@ pub struct Community {
 pub struct Membership {
     pub community: Pubkey,
     pub user: Pubkey,
-    pub role: String,       // "member", "moderator", "admin"
+    pub role: MemberRole,
     pub voting_power: u64,  // Weighted voting power
     pub join_time: i64,
     pub reputation_score: u64,
     pub is_active: bool,
+    pub deposited_amount: u64,      // Voting tokens deposited with the community
+    pub deposit_locked_until: i64,  // Deposits stay locked until proposals voted on have ended
+    pub conviction_staked: u64,     // Voting power currently staked on funding requests
+    pub delegated_to: Option<Pubkey>, // Faction casting this member's voting power
+    pub qualifying_mint: Option<Pubkey>, // NFT the member joined with under a collection requirement
+    pub removed: bool,              // Removed by an admin or an executed proposal, barring a rejoin
+}
+
+// Curator council that approves a community's investment proposals
+#[account]
+pub struct CuratorCouncil {
+    pub community: Pubkey,
+    pub curators: Vec<Pubkey>,
+    pub created_at: i64,
+}
+
+// Records a curator's approval of an investment proposal
+#[account]
+pub struct CouncilApproval {
+    pub proposal: Pubkey,
+    pub curator: Pubkey,
+    pub approved_at: i64,
+}
+
+// Invite to an invite-only community, consumed when the invitee joins
+#[account]
+pub struct MembershipInvite {
+    pub community: Pubkey,
+    pub invitee: Pubkey,
+    pub inviter: Pubkey,     // Admin who paid for the invite and receives its rent back
+    pub created_at: i64,
+    pub expires_at: i64,     // 0 = never expires
+}
+
+// Application to join an application-review community
+#[account]
+pub struct MembershipApplication {
+    pub community: Pubkey,
+    pub applicant: Pubkey,
+    pub message: String,
+    pub status: ApplicationStatus,
+    pub submitted_at: i64,
+    pub reviewed_by: Option<Pubkey>,
+    pub reviewed_at: Option<i64>,
 }
 
 // Proposal for governance actions
@@ -88,15 +489,44 @@ pub struct Proposal {
     pub is_investment_proposal: bool,  // Flag for investment proposals
     pub target_collection: Option<Pubkey>, // If it's an investment proposal
     pub investment_amount: Option<u64>,    // If it's an investment proposal
+    pub canceled_by: Option<Pubkey>,       // Proposer who canceled or moderator who vetoed
+    pub canceled_at: Option<i64>,
+    pub succeeded_at: Option<i64>,         // Start of the execution timelock
+    pub action: Option<ProposalAction>,    // Carried out natively on execution
+    pub voting_mode: VotingMode,           // Copied from the community at creation
+    pub ballot_type: BallotType,
+    pub options: Vec<ProposalOption>,      // Empty for binary proposals
+    pub ballot_weight: u64,                // Total weight of ballots cast on multi-option proposals
+    pub winning_option: Option<u8>,
+    pub is_secret_ballot: bool,            // Votes are committed as hashes and revealed after end_time
+    pub reveal_end_time: i64,              // Equals end_time for open ballots
+    pub deposit_amount: u64,               // Outstanding proposal deposit, 0 once settled
+    pub deposit_mint: Option<Pubkey>,      // None for SOL held on the proposal account
+    pub quorum_reached: bool,
+    pub council_threshold: u8,             // Curator approvals required, copied from the community
+    pub council_approvers: Vec<Pubkey>,    // Curators who approved, recounted against the council at finalization
+    pub reputation_voting: bool,           // Copied from the governance config at creation
+    pub signed_vote_count: u32,            // Votes submitted by relayers with member signatures
+    pub snapshot_voting_power: u64,        // Community voting power when the proposal started
+    pub snapshot_escrow_weight: u64,       // Community escrow weight when the proposal started
+    pub vote_count: u32,                   // Votes, ballots and commitments recorded
 }
 
 // Stores executable instructions for a proposal
 #[account]
 pub struct ProposalInstructions {
     pub proposal: Pubkey,
+    pub option_index: u8,         // Option these instructions belong to (0 for binary proposals)
     pub instructions: Vec<ProposalInstruction>,
 }
 
+// Vote a member signed off chain, submitted by a relayer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SignedVote {
+    pub voter: Pubkey,
+    pub vote_type: VoteType,
+}
+
 // Records individual votes
 #[account]
 pub struct Vote {
@@ -105,6 +535,9 @@ pub struct Vote {
     pub vote_type: VoteType,
     pub voting_power: u64,
     pub vote_time: i64,
+    pub choices: Vec<u8>,         // Option indexes for multi-option ballots, in rank order
+    pub commitment: Option<[u8; 32]>, // Secret ballot hash of (voter, vote, choices, salt)
+    pub is_revealed: bool,        // Always true for open ballots
 }
 
 // Reputation system for users
@@ -117,19 +550,71 @@ pub struct ReputationScore {
     pub contribution_score: u64,  // Based on community contributions
     pub total_score: u64,         // Overall reputation score
     pub last_updated: i64,
+    pub decayed_at: i64,          // Last time participation decay was applied
+    pub cap_epoch: u64,           // Epoch that epoch_participation counts towards
+    pub epoch_participation: u64, // Participation points earned in cap_epoch
+}
+
+// Points a user earned within one community, rolled up into their governance-level ReputationScore.
+// Participation decays at the governance rate on each breakdown's own clock, so breakdowns summed
+// over communities match the governance-level score up to per-epoch rounding.
+#[account]
+pub struct CommunityReputation {
+    pub community: Pubkey,
+    pub user: Pubkey,
+    pub investment_score: u64,
+    pub participation_score: u64,
+    pub contribution_score: u64,
+    pub total_score: u64,
+    pub last_updated: i64,
+    pub decayed_at: i64,
+}
+
+impl CommunityReputation {
+    pub fn refresh_total_score(&mut self) -> Result<()> {
+        self.total_score = self.investment_score
+            .checked_add(self.participation_score).ok_or(GovernanceError::MathOverflow)?
+            .checked_add(self.contribution_score).ok_or(GovernanceError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+// Reputation returned to other programs through return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ReputationView {
+    pub user: Pubkey,
+    pub governance: Pubkey,
+    pub investment_score: u64,
+    pub participation_score: u64,       // With pending decay applied
+    pub contribution_score: u64,
+    pub total_score: u64,
+    pub community: Option<Pubkey>,      // Community of the included breakdown
+    pub community_score: u64,           // Points earned in that community, 0 without a breakdown
 }
 
-// Activity reward tracker
+// Activity reward tracker, accrued per user and epoch
 #[account]
 pub struct ActivityReward {
     pub user: Pubkey,
     pub governance: Pubkey,
-    pub reward_type: String,      // "vote", "proposal", "contribution"
-    pub amount: u64,
-    pub timestamp: i64,
+    pub epoch: u64,
+    pub amount: u64,              // Reward points accrued during the epoch
+    pub timestamp: i64,           // Last accrual time
     pub is_claimed: bool,
 }
 
+// Governance-wide pool of reward tokens paying out activity rewards
+#[account]
+pub struct RewardPool {
+    pub governance: Pubkey,
+    pub reward_mint: Pubkey,
+    pub vault: Pubkey,
+    pub reward_rate: u64,         // Reward token base units paid per reward point
+    pub total_funded: u64,
+    pub total_claimed: u64,
+    pub bump: u8,
+}
+
 // Faction/Group for strategic voting
 #[account]
 pub struct StrategicFaction {
@@ -141,6 +626,8 @@ pub struct StrategicFaction {
     pub creation_time: i64,
     pub investment_strategy: String, // Description of faction's strategy
     pub is_active: bool,
+    pub admin_count: u64,
+    pub admin_version: u64,          // Bumped whenever the admins change, voiding pending spend approvals
 }
 
 // Faction membership
@@ -150,6 +637,74 @@ pub struct FactionMembership {
     pub user: Pubkey,
     pub join_time: i64,
     pub is_active: bool,
+    pub role: MemberRole,           // Faction admins cast the bloc's votes and manage members
+    pub delegation_count: u64,      // Communities whose voting power the member delegated to the faction
+}
+
+// Voting power delegated to a faction within one community
+#[account]
+pub struct FactionBloc {
+    pub faction: Pubkey,
+    pub community: Pubkey,
+    pub delegated_weight: u64,
+    pub delegator_count: u64,
+    pub locked_until: i64,          // Delegations stay locked until proposals the bloc voted on have ended
+}
+
+// A member's delegation of community voting power to a faction
+#[account]
+pub struct FactionDelegation {
+    pub faction: Pubkey,
+    pub community: Pubkey,
+    pub user: Pubkey,
+    pub weight: u64,                // Voting power at delegation time
+    pub delegated_at: i64,
+}
+
+// Internal faction vote deciding how the bloc votes on a proposal
+#[account]
+pub struct FactionVote {
+    pub faction: Pubkey,
+    pub proposal: Pubkey,
+    pub for_weight: u64,
+    pub against_weight: u64,
+    pub abstain_weight: u64,
+    pub is_cast: bool,
+    pub cast_vote_type: Option<VoteType>,
+    pub cast_weight: u64,
+}
+
+// A faction member's internal vote
+#[account]
+pub struct FactionMemberVote {
+    pub faction_vote: Pubkey,
+    pub user: Pubkey,
+    pub vote_type: VoteType,
+    pub weight: u64,
+    pub vote_time: i64,
+}
+
+// Treasury pooled by faction members, owned by a PDA
+#[account]
+pub struct FactionTreasury {
+    pub faction: Pubkey,
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub total_contributed: u64,
+    pub total_spent: u64,
+    pub bump: u8,
+    pub spend_count: u64,
+}
+
+// Faction treasury spend, paid out once a majority of the faction's admins approved it
+#[account]
+pub struct FactionSpend {
+    pub treasury: Pubkey,
+    pub destination: Pubkey,        // Token account receiving the funds
+    pub amount: u64,
+    pub admin_version: u64,         // Approvals only count while the faction's admins are unchanged
+    pub approvers: Vec<Pubkey>,
+    pub created_at: i64,
 }
 
 // Investment strategy record
@@ -168,6 +723,252 @@ pub struct InvestmentStrategy {
     pub is_active: bool,
 }
 
+// Position of a strategy portfolio in one target collection
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PortfolioHolding {
+    pub collection: Pubkey,
+    pub asset_account: Pubkey,    // Portfolio-owned token account of the collection's fraction tokens, default until set
+    pub quantity: u64,            // Units held, valued at the collection's price feed
+}
+
+// Assets of an investment strategy, owned by a PDA and funded by executed proposals
+#[account]
+pub struct StrategyPortfolio {
+    pub strategy: Pubkey,
+    pub community: Pubkey,
+    pub base_mint: Pubkey,        // Currency the portfolio is funded and valued in
+    pub cash_account: Pubkey,     // Uninvested funds
+    pub total_funded: u64,
+    pub cash: u64,                // Funded cash not yet spent, transfers made straight into the cash account don't count
+    pub holdings: Vec<PortfolioHolding>, // One per strategy target collection
+    pub last_nav: u64,
+    pub last_valued_at: i64,
+    pub reputation_credit: i64,   // Investment points currently credited to the strategy creator
+    pub bump: u8,
+}
+
+// Direction of a rebalance order
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RebalanceSide {
+    Buy,
+    Sell,
+}
+
+// One trade needed to move a holding back to its target allocation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RebalanceOrder {
+    pub collection: Pubkey,
+    pub side: RebalanceSide,
+    pub quantity: u64,
+    pub price: u64,               // Price per unit when the plan was computed
+    pub filled: bool,
+}
+
+// Orders computed from a portfolio's drift, filled once governance approves them
+#[account]
+pub struct RebalancePlan {
+    pub portfolio: Pubkey,
+    pub strategy: Pubkey,
+    pub community: Pubkey,
+    pub orders: Vec<RebalanceOrder>,
+    pub nav: u64,                 // Portfolio value the targets were computed from
+    pub computed_at: i64,
+    pub approved: bool,
+    pub bump: u8,
+    pub frozen: bool,             // Up for approval by a proposal, kept until filled or expired
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FederatedStatus {
+    Voting,
+    Approved,
+    Rejected,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FederatedDecision {
+    Pending,                      // Counts as a rejection once the deadline has passed
+    Approved,
+}
+
+// Terms a community is asked to approve in a federated proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FederatedParticipantTerms {
+    pub community: Pubkey,
+    pub required: bool,           // The federated proposal is rejected unless a required community approves by the deadline
+    pub contribution: u64,        // Drawn from the community's treasury once approved
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FederatedParticipant {
+    pub community: Pubkey,
+    pub required: bool,
+    pub contribution: u64,
+    pub decision: FederatedDecision, // Approved by executing the community's own proposal
+    pub is_funded: bool,
+}
+
+// Proposal spanning several communities of one governance, each voting under its own rules
+#[account]
+pub struct FederatedProposal {
+    pub governance: Pubkey,
+    pub proposer: Pubkey,
+    pub title: String,
+    pub description: String,
+    pub mint: Pubkey,             // Token the contributions are paid in
+    pub destination: Pubkey,      // Token account receiving the contributions
+    pub participants: Vec<FederatedParticipant>,
+    pub min_approvals: u8,        // Approving communities needed, including all required ones
+    pub status: FederatedStatus,
+    pub created_at: i64,
+    pub deadline: i64,            // Communities that have not approved by then count as rejecting
+}
+
+// Conviction voting parameters for a community, all ratios in basis points
+#[account]
+pub struct ConvictionConfig {
+    pub community: Pubkey,
+    pub decay_bps: u64,             // Share of conviction retained per step (alpha)
+    pub max_ratio_bps: u64,         // Largest share of the treasury a single request can take (beta)
+    pub weight_bps: u64,            // Threshold scaling factor (rho)
+    pub step_seconds: i64,          // Length of one conviction step
+    pub min_effective_weight: u64,  // Floor for the staked weight used in thresholds
+    pub total_staked: u64,          // Weight staked across all active requests
+    pub request_count: u64,
+}
+
+// Continuous funding request passed by accumulated conviction
+#[account]
+pub struct FundingRequest {
+    pub community: Pubkey,
+    pub treasury: Pubkey,
+    pub requester: Pubkey,
+    pub beneficiary: Pubkey,        // Token account receiving the funds
+    pub description: String,
+    pub requested_amount: u64,
+    pub staked_weight: u64,
+    pub conviction: u64,
+    pub last_updated: i64,          // Time conviction was last accrued up to
+    pub status: FundingStatus,
+    pub created_at: i64,
+    pub funded_at: Option<i64>,
+}
+
+// Voting power a member has staked on a funding request
+#[account]
+pub struct ConvictionStake {
+    pub request: Pubkey,
+    pub user: Pubkey,
+    pub weight: u64,
+    pub last_updated: i64,
+}
+
+// Voting tokens locked until unlock_time for decaying, boosted voting power
+#[account]
+pub struct VoteEscrow {
+    pub community: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub decimals: u8,               // Decimals of the locked mint
+    pub lock_start: i64,
+    pub unlock_time: i64,
+    pub max_lock_duration: i64,     // Community maximum at lock time
+}
+
+impl VoteEscrow {
+    // Locked amount scaled by the remaining lock time, decaying linearly to zero at unlock
+    pub fn voting_power_at(&self, timestamp: i64) -> u64 {
+        if timestamp >= self.unlock_time || self.max_lock_duration <= 0 {
+            return 0;
+        }
+        
+        let remaining = (self.unlock_time - timestamp).min(self.max_lock_duration) as u128;
+        (self.amount as u128 * remaining / self.max_lock_duration as u128) as u64
+    }
+    
+    // Activity reward boost of up to 150% for a maximum-length lock, decaying with the lock
+    pub fn reward_boost_bps(&self, timestamp: i64) -> u64 {
+        if self.amount == 0 || timestamp >= self.unlock_time || self.max_lock_duration <= 0 {
+            return 0;
+        }
+        
+        let remaining = (self.unlock_time - timestamp).min(self.max_lock_duration) as u128;
+        (15_000 * remaining / self.max_lock_duration as u128) as u64
+    }
+}
+
+// Sources of a member's voting power from a point in time on
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct PowerCheckpoint {
+    pub timestamp: i64,
+    pub voting_power: u64,          // Membership power, or delegated weight for a faction bloc
+    pub deposited_amount: u64,
+    pub escrow_amount: u64,
+    pub escrow_decimals: u8,
+    pub escrow_unlock_time: i64,
+    pub escrow_max_lock: i64,
+    pub delegated: bool,            // Power was delegated to a faction bloc
+}
+
+impl PowerCheckpoint {
+    // Escrow power as the lock stood at this checkpoint, evaluated at the timestamp
+    pub fn escrow_power_at(&self, timestamp: i64) -> u64 {
+        if timestamp >= self.escrow_unlock_time || self.escrow_max_lock <= 0 {
+            return 0;
+        }
+        
+        let remaining = (self.escrow_unlock_time - timestamp).min(self.escrow_max_lock) as u128;
+        (self.escrow_amount as u128 * remaining / self.escrow_max_lock as u128) as u64
+    }
+}
+
+// Voting power history of a member in a community, or of a faction bloc
+#[account]
+pub struct VotingPowerCheckpoints {
+    pub community: Pubkey,
+    pub owner: Pubkey,              // Member, or faction for a bloc
+    pub checkpoints: Vec<PowerCheckpoint>,
+}
+
+impl VotingPowerCheckpoints {
+    // Latest checkpoint taken strictly before the timestamp
+    pub fn power_at(&self, timestamp: i64) -> Option<&PowerCheckpoint> {
+        self.checkpoints.iter().rev().find(|checkpoint| checkpoint.timestamp < timestamp)
+    }
+    
+    // Appends a checkpoint derived from the latest one, replacing it when taken in the same second.
+    // History is never dropped, so snapshots of long-running proposals stay available.
+    pub fn record(&mut self, timestamp: i64, update: impl FnOnce(&mut PowerCheckpoint)) {
+        let mut checkpoint = self.checkpoints.last().cloned().unwrap_or_default();
+        checkpoint.timestamp = timestamp;
+        update(&mut checkpoint);
+        
+        if self.checkpoints.last().is_some_and(|last| last.timestamp == timestamp) {
+            self.checkpoints.pop();
+        }
+        self.checkpoints.push(checkpoint);
+    }
+    
+    pub fn space(&self) -> usize {
+        8 + 32 + 32 + 4 + self.checkpoints.len() * POWER_CHECKPOINT_SIZE
+    }
+}
+
+// Integer square root used for quadratic vote weights
+pub fn integer_sqrt(value: u64) -> u64 {
+    if value < 2 {
+        return value;
+    }
+    
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
 // Implementation errors
 #[error_code]
 pub enum GovernanceError {
@@ -189,4 +990,196 @@ pub enum GovernanceError {
     MathOverflow,
     #[msg("Invalid investment allocation")]
     InvalidInvestmentAllocation,
-} 
\ No newline at end of file
+    #[msg("The veto window for this proposal has closed")]
+    VetoWindowClosed,
+    #[msg("Invalid governance configuration")]
+    InvalidGovernanceConfig,
+    #[msg("The execution timelock has not elapsed yet")]
+    TimelockNotElapsed,
+    #[msg("The execution window for this proposal has expired")]
+    ExecutionWindowExpired,
+    #[msg("The member cannot be moved to the requested role")]
+    InvalidRoleChange,
+    #[msg("The community authority cannot be demoted or removed")]
+    CommunityAuthorityProtected,
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Treasury accounts are missing or do not match the proposal")]
+    InvalidTreasuryAccount,
+    #[msg("Epoch does not match the current reward epoch")]
+    InvalidEpoch,
+    #[msg("Rewards can only be claimed once the epoch has ended")]
+    EpochNotEnded,
+    #[msg("Reward has already been claimed")]
+    RewardAlreadyClaimed,
+    #[msg("Voting tokens are locked until active votes have ended")]
+    DepositLocked,
+    #[msg("Withdraw deposited voting tokens and conviction stakes before leaving")]
+    OutstandingDeposit,
+    #[msg("The community voting vault is missing or already exists")]
+    InvalidVotingMint,
+    #[msg("Invalid proposal options")]
+    InvalidProposalOptions,
+    #[msg("Ballot choices are invalid for this proposal")]
+    InvalidBallot,
+    #[msg("Proposal instructions are missing or do not match the winning option")]
+    InvalidProposalInstructions,
+    #[msg("Votes on this proposal must be committed and revealed")]
+    SecretBallotRequired,
+    #[msg("This proposal does not use secret ballots")]
+    NotSecretBallot,
+    #[msg("The reveal phase is not open")]
+    RevealNotOpen,
+    #[msg("Revealed vote does not match the commitment")]
+    CommitmentMismatch,
+    #[msg("Vote has already been revealed")]
+    AlreadyRevealed,
+    #[msg("Funding request is not active")]
+    FundingRequestNotActive,
+    #[msg("Not enough unstaked voting power")]
+    InsufficientVotingPower,
+    #[msg("Invalid vote-escrow lock duration")]
+    InvalidLockDuration,
+    #[msg("Vote-escrow tokens are still locked")]
+    EscrowLocked,
+    #[msg("This instruction can only be invoked by an executed proposal")]
+    ProposalExecutionRequired,
+    #[msg("Proposal action is invalid or its accounts are missing")]
+    InvalidProposalAction,
+    #[msg("Proposal deposit accounts are missing or invalid")]
+    InvalidDepositAccount,
+    #[msg("Proposal deposit has already been settled")]
+    DepositAlreadySettled,
+    #[msg("The community's membership requirement is not met")]
+    MembershipRequirementNotMet,
+    #[msg("Membership requirement accounts are missing or invalid")]
+    InvalidRequirementAccount,
+    #[msg("Invite is missing, expired or issued to another user")]
+    InvalidInvite,
+    #[msg("Membership application is missing or not approved")]
+    ApplicationNotApproved,
+    #[msg("Membership application has already been reviewed")]
+    ApplicationAlreadyReviewed,
+    #[msg("User is not a curator of this community")]
+    NotCurator,
+    #[msg("Invalid curator council change")]
+    InvalidCouncilChange,
+    #[msg("Voting power is delegated to a faction")]
+    VotingPowerDelegated,
+    #[msg("Faction delegation is locked until the bloc's votes have ended")]
+    DelegationLocked,
+    #[msg("Withdraw faction delegations before leaving")]
+    FactionDelegationActive,
+    #[msg("The faction founder cannot be demoted or removed")]
+    FactionFounderProtected,
+    #[msg("Price feed is missing, stale or does not match the portfolio")]
+    InvalidPriceFeed,
+    #[msg("Rebalance plan has not been approved")]
+    RebalanceNotApproved,
+    #[msg("Rebalance plan is up for approval or still has unfilled orders")]
+    RebalancePending,
+    #[msg("Rebalance order does not exist, is already filled or its assets do not match")]
+    InvalidRebalanceOrder,
+    #[msg("Federated proposal participants or terms are invalid")]
+    InvalidFederation,
+    #[msg("Federated proposal is still waiting on community decisions")]
+    FederationUndecided,
+    #[msg("Vote signature is missing or does not match the signed vote")]
+    InvalidSignedVote,
+    #[msg("No voting power checkpoint precedes the proposal start")]
+    NoVotingPowerSnapshot,
+    #[msg("A majority of recognized communities has not approved the config amendment")]
+    AmendmentQuorumNotReached,
+    #[msg("User is already an active member of this community")]
+    AlreadyMember,
+    #[msg("Curator council approvals are still pending")]
+    CouncilApprovalPending,
+    #[msg("Curator council account is missing or does not match the proposal")]
+    InvalidCouncilAccount,
+    #[msg("User is already an active member of this faction")]
+    AlreadyFactionMember,
+    #[msg("Community reputation does not belong to a community of this governance")]
+    InvalidCommunityReputation,
+    #[msg("Removed members cannot rejoin the community")]
+    MemberRemoved,
+    #[msg("Proposal can still be vetoed")]
+    VetoWindowOpen,
+    #[msg("Only communities recognized by the governance authority can approve config amendments")]
+    CommunityNotRecognized,
+    #[msg("Community is not active")]
+    CommunityInactive,
+    #[msg("Proposal already has the maximum number of curator approvals")]
+    TooManyApprovers,
+    #[msg("User is not an active member of this faction")]
+    NotFactionMember,
+    #[msg("Faction already has the maximum number of admins")]
+    TooManyFactionAdmins,
+    #[msg("Faction admins changed since the spend was proposed")]
+    FactionAdminsChanged,
+    #[msg("A majority of faction admins has not approved the spend")]
+    FactionSpendNotApproved,
+    #[msg("Rebalance plan is too old to be filled")]
+    RebalancePlanExpired,
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn checkpoints(entries: &[(i64, u64)]) -> VotingPowerCheckpoints {
+        let mut history = VotingPowerCheckpoints {
+            community: Pubkey::default(),
+            owner: Pubkey::default(),
+            checkpoints: Vec::new(),
+        };
+        for (timestamp, voting_power) in entries {
+            history.record(*timestamp, |checkpoint| checkpoint.voting_power = *voting_power);
+        }
+        history
+    }
+    
+    #[test]
+    fn integer_sqrt_rounds_down() {
+        assert_eq!(integer_sqrt(0), 0);
+        assert_eq!(integer_sqrt(1), 1);
+        assert_eq!(integer_sqrt(3), 1);
+        assert_eq!(integer_sqrt(4), 2);
+        assert_eq!(integer_sqrt(99), 9);
+        assert_eq!(integer_sqrt(100), 10);
+        assert_eq!(integer_sqrt(u64::MAX), u32::MAX as u64);
+    }
+    
+    #[test]
+    fn power_at_uses_latest_checkpoint_strictly_before_timestamp() {
+        let history = checkpoints(&[(10, 1), (20, 2), (30, 3)]);
+        
+        assert!(history.power_at(10).is_none());
+        assert_eq!(history.power_at(11).unwrap().voting_power, 1);
+        assert_eq!(history.power_at(20).unwrap().voting_power, 1);
+        assert_eq!(history.power_at(21).unwrap().voting_power, 2);
+        assert_eq!(history.power_at(i64::MAX).unwrap().voting_power, 3);
+    }
+    
+    #[test]
+    fn record_derives_from_latest_and_replaces_same_second() {
+        let mut history = checkpoints(&[(10, 1)]);
+        history.record(10, |checkpoint| checkpoint.deposited_amount = 5);
+        history.record(20, |checkpoint| checkpoint.voting_power += 1);
+        
+        assert_eq!(history.checkpoints.len(), 2);
+        assert_eq!(history.checkpoints[0].voting_power, 1);
+        assert_eq!(history.checkpoints[0].deposited_amount, 5);
+        assert_eq!(history.checkpoints[1].voting_power, 2);
+        assert_eq!(history.checkpoints[1].deposited_amount, 5);
+    }
+    
+    #[test]
+    fn record_keeps_full_history() {
+        let entries: Vec<(i64, u64)> = (0..INITIAL_POWER_CHECKPOINTS as i64 * 2).map(|index| (index, index as u64)).collect();
+        let history = checkpoints(&entries);
+        
+        assert_eq!(history.checkpoints.len(), entries.len());
+        assert_eq!(history.power_at(1).unwrap().voting_power, 0);
+        assert_eq!(history.space(), 8 + 32 + 32 + 4 + entries.len() * POWER_CHECKPOINT_SIZE);
+    }
+}
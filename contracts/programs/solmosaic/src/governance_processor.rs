@@ -1,515 +1,6811 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use anchor_spl::token::Token;
 use crate::governance::*;
+use crate::{CollectionPriceFeed, CollectionRating, FractionalVault, RatingSystem};
 
-pub fn initialize_governance(
-    ctx: Context<InitializeGovernance>,
-    name: String,
-    min_vote_threshold: u64,
-    voting_period: i64,
-) -> Result<()> {
-    let governance = &mut ctx.accounts.governance;
-    governance.authority = ctx.accounts.authority.key();
-    governance.name = name;
-    governance.min_vote_threshold = min_vote_threshold;
-    governance.voting_period = voting_period;
-    governance.proposal_count = 0;
-    governance.total_communities = 0;
-    governance.is_active = true;
-    Ok(())
-}
+// Token metadata program, whose verified creators tie an NFT to its collection
+// metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s
+const TOKEN_METADATA_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    11, 112, 101, 177, 227, 209, 124, 69, 56, 157, 82, 127, 107, 4, 195, 205,
+    88, 184, 108, 115, 26, 160, 253, 181, 73, 182, 209, 188, 3, 248, 41, 70,
+]);
 
-pub fn create_community(
-    ctx: Context<CreateCommunity>,
-    name: String,
-    description: String,
-) -> Result<()> {
-    let governance = &mut ctx.accounts.governance;
-    let community = &mut ctx.accounts.community;
-    
-    community.governance = governance.key();
-    community.name = name;
-    community.description = description;
-    community.authority = ctx.accounts.authority.key();
-    community.member_count = 1; // Creator is the first member
-    community.proposal_count = 0;
-    community.creation_time = Clock::get()?.unix_timestamp;
-    community.is_active = true;
+// Weight a member adds to a proposal under its voting mode, from their checkpointed power at the proposal start
+fn calculate_vote_weight(
+    proposal: &Proposal,
+    checkpoints: &VotingPowerCheckpoints,
+) -> Result<u64> {
+    let snapshot = checkpoints.power_at(proposal.start_time)
+        .ok_or(GovernanceError::NoVotingPowerSnapshot)?;
     
-    // Create membership for community creator
-    let membership = &mut ctx.accounts.membership;
-    membership.community = community.key();
-    membership.user = ctx.accounts.authority.key();
-    membership.role = "admin".to_string();
-    membership.voting_power = 100; // Admin starts with high voting power
-    membership.join_time = Clock::get()?.unix_timestamp;
-    membership.reputation_score = 100; // Initial reputation score
-    membership.is_active = true;
-    
-    // Increment total communities counter
-    governance.total_communities = governance.total_communities.checked_add(1)
-        .ok_or(GovernanceError::MathOverflow)?;
+    // Delegated power is cast by the faction bloc
+    if snapshot.delegated {
+        return Err(GovernanceError::VotingPowerDelegated.into());
+    }
     
-    Ok(())
+    let escrow_power = snapshot.escrow_power_at(proposal.start_time);
+    let weight = match proposal.voting_mode {
+        VotingMode::Linear => {
+            // Escrow power counts in whole tokens next to membership voting power
+            let scale = 10u64.checked_pow(snapshot.escrow_decimals as u32).ok_or(GovernanceError::MathOverflow)?;
+            snapshot.voting_power
+                .checked_add(escrow_power / scale)
+                .ok_or(GovernanceError::MathOverflow)?
+        },
+        VotingMode::Quadratic => {
+            integer_sqrt(snapshot.deposited_amount.checked_add(escrow_power).ok_or(GovernanceError::MathOverflow)?)
+        },
+    };
+    Ok(weight)
 }
 
-pub fn join_community(
-    ctx: Context<JoinCommunity>,
+// Checkpoints a member's voting power, deposits and delegation
+fn checkpoint_membership(checkpoints: &mut VotingPowerCheckpoints, membership: &Membership, current_time: i64) {
+    checkpoints.community = membership.community;
+    checkpoints.owner = membership.user;
+    checkpoints.record(current_time, |checkpoint| {
+        checkpoint.voting_power = membership.voting_power;
+        checkpoint.deposited_amount = membership.deposited_amount;
+        checkpoint.delegated = membership.delegated_to.is_some();
+    });
+}
+
+// Checkpoints a member's vote-escrow lock, a closed lock is recorded as empty
+fn checkpoint_escrow(
+    checkpoints: &mut VotingPowerCheckpoints,
+    community: Pubkey,
+    user: Pubkey,
+    escrow: Option<&VoteEscrow>,
+    current_time: i64,
+) {
+    checkpoints.community = community;
+    checkpoints.owner = user;
+    checkpoints.record(current_time, |checkpoint| {
+        checkpoint.escrow_amount = escrow.map_or(0, |escrow| escrow.amount);
+        checkpoint.escrow_decimals = escrow.map_or(0, |escrow| escrow.decimals);
+        checkpoint.escrow_unlock_time = escrow.map_or(0, |escrow| escrow.unlock_time);
+        checkpoint.escrow_max_lock = escrow.map_or(0, |escrow| escrow.max_lock_duration);
+    });
+}
+
+// Checkpoints the weight delegated to a faction bloc
+fn checkpoint_bloc(checkpoints: &mut VotingPowerCheckpoints, bloc: &FactionBloc, current_time: i64) {
+    checkpoints.community = bloc.community;
+    checkpoints.owner = bloc.faction;
+    checkpoints.record(current_time, |checkpoint| checkpoint.voting_power = bloc.delegated_weight);
+}
+
+// Grows a checkpoint account to hold every recorded checkpoint, the payer covers the extra rent
+fn fit_checkpoints<'info>(
+    checkpoints: &Account<'info, VotingPowerCheckpoints>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
 ) -> Result<()> {
-    let community = &mut ctx.accounts.community;
-    let membership = &mut ctx.accounts.membership;
+    let info = checkpoints.to_account_info();
+    let space = checkpoints.space();
+    if info.data_len() >= space {
+        return Ok(());
+    }
     
-    membership.community = community.key();
-    membership.user = ctx.accounts.user.key();
-    membership.role = "member".to_string();
-    membership.voting_power = 10; // New members start with base voting power
-    membership.join_time = Clock::get()?.unix_timestamp;
-    membership.reputation_score = 10; // Initial reputation score
-    membership.is_active = true;
+    let rent_due = Rent::get()?.minimum_balance(space).saturating_sub(info.lamports());
+    if rent_due > 0 {
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: payer,
+            to: info.clone(),
+        };
+        anchor_lang::system_program::transfer(CpiContext::new(system_program, transfer_ix), rent_due)?;
+    }
     
-    // Increment community member count
-    community.member_count = community.member_count.checked_add(1)
-        .ok_or(GovernanceError::MathOverflow)?;
+    info.realloc(space, false)?;
+    Ok(())
+}
+
+// Participation points left after decaying by the governance rate for each reward epoch elapsed since decayed_at
+fn decayed_participation(participation: u64, governance: &GovernanceConfig, decayed_at: i64, current_time: i64) -> u64 {
+    let elapsed = governance.epoch_at(current_time).saturating_sub(governance.epoch_at(decayed_at));
+    if elapsed > MAX_DECAY_EPOCHS {
+        return 0;
+    }
     
-    // Initialize reputation score for new member
-    let reputation = &mut ctx.accounts.reputation;
-    reputation.user = ctx.accounts.user.key();
-    reputation.governance = ctx.accounts.community.governance;
-    reputation.investment_score = 0;
-    reputation.participation_score = 10; // Initial participation score for joining
-    reputation.contribution_score = 0;
-    reputation.total_score = 10;
-    reputation.last_updated = Clock::get()?.unix_timestamp;
+    let keep_bps = 10_000u128.saturating_sub(governance.reputation_decay_bps as u128);
+    let mut participation = participation as u128;
+    for _ in 0..elapsed {
+        participation = participation * keep_bps / 10_000;
+    }
+    participation as u64
+}
+
+// Decays a governance-level reputation, contribution and investment scores are backed by funds and do not decay
+fn decay_reputation(reputation: &mut ReputationScore, governance: &GovernanceConfig, current_time: i64) -> Result<()> {
+    reputation.participation_score = decayed_participation(reputation.participation_score, governance, reputation.decayed_at, current_time);
+    reputation.decayed_at = current_time;
     
+    // Recalculate total score
+    reputation.total_score = reputation.investment_score
+        .checked_add(reputation.participation_score).ok_or(GovernanceError::MathOverflow)?
+        .checked_add(reputation.contribution_score).ok_or(GovernanceError::MathOverflow)?;
     Ok(())
 }
 
-pub fn create_proposal(
-    ctx: Context<CreateProposal>,
-    title: String,
-    description: String,
-    is_investment_proposal: bool,
-    target_collection: Option<Pubkey>,
-    investment_amount: Option<u64>,
+// Decays a community breakdown the same way, keeping it in line with the governance-level score
+fn decay_breakdown(breakdown: &mut CommunityReputation, governance: &GovernanceConfig, current_time: i64) -> Result<()> {
+    breakdown.participation_score = decayed_participation(breakdown.participation_score, governance, breakdown.decayed_at, current_time);
+    breakdown.decayed_at = current_time;
+    breakdown.refresh_total_score()
+}
+
+// Awards participation points after decay, up to the governance cap for the current epoch
+fn award_participation(
+    reputation: &mut ReputationScore,
+    breakdown: &mut CommunityReputation,
+    governance: &GovernanceConfig,
+    points: u64,
+    current_time: i64,
 ) -> Result<()> {
-    // Check if user is a member of the community
-    let membership = &ctx.accounts.membership;
-    if !membership.is_active {
-        return Err(GovernanceError::NotCommunityMember.into());
-    }
+    decay_reputation(reputation, governance, current_time)?;
+    decay_breakdown(breakdown, governance, current_time)?;
     
-    let community = &mut ctx.accounts.community;
-    let proposal = &mut ctx.accounts.proposal;
-    let governance = &ctx.accounts.governance;
+    let epoch = governance.epoch_at(current_time);
+    if reputation.cap_epoch != epoch {
+        reputation.cap_epoch = epoch;
+        reputation.epoch_participation = 0;
+    }
     
-    // Set up proposal details
-    proposal.governance = governance.key();
-    proposal.community = community.key();
-    proposal.proposer = ctx.accounts.authority.key();
-    proposal.title = title;
-    proposal.description = description;
-    proposal.status = ProposalStatus::Active;
+    let points = if governance.participation_cap == 0 {
+        points
+    } else {
+        points.min(governance.participation_cap.saturating_sub(reputation.epoch_participation))
+    };
     
-    let current_time = Clock::get()?.unix_timestamp;
-    proposal.start_time = current_time;
-    proposal.end_time = current_time.checked_add(governance.voting_period)
+    reputation.epoch_participation = reputation.epoch_participation.checked_add(points)
+        .ok_or(GovernanceError::MathOverflow)?;
+    reputation.participation_score = reputation.participation_score.checked_add(points)
         .ok_or(GovernanceError::MathOverflow)?;
+    reputation.last_updated = current_time;
+    breakdown.participation_score = breakdown.participation_score.checked_add(points)
+        .ok_or(GovernanceError::MathOverflow)?;
+    breakdown.last_updated = current_time;
     
-    proposal.instruction_count = 0;
-    proposal.for_votes = 0;
-    proposal.against_votes = 0;
-    proposal.abstain_votes = 0;
-    proposal.executed_at = None;
-    proposal.is_investment_proposal = is_investment_proposal;
-    proposal.target_collection = target_collection;
-    proposal.investment_amount = investment_amount;
+    // Recalculate total scores
+    reputation.total_score = reputation.investment_score
+        .checked_add(reputation.participation_score).ok_or(GovernanceError::MathOverflow)?
+        .checked_add(reputation.contribution_score).ok_or(GovernanceError::MathOverflow)?;
+    breakdown.refresh_total_score()
+}
+
+// Deducts a penalty from the participation points of a proposer
+fn penalize_reputation(
+    reputation: &mut ReputationScore,
+    breakdown: &mut CommunityReputation,
+    governance: &GovernanceConfig,
+    penalty: u64,
+    current_time: i64,
+) -> Result<()> {
+    decay_reputation(reputation, governance, current_time)?;
+    decay_breakdown(breakdown, governance, current_time)?;
     
-    // Increment proposal count
-    community.proposal_count = community.proposal_count.checked_add(1)
-        .ok_or(GovernanceError::MathOverflow)?;
+    // Only points earned in this community are taken, from both levels
+    let penalty = penalty.min(breakdown.participation_score);
     
-    // Update reputation score for proposal creation
-    let reputation = &mut ctx.accounts.reputation;
-    reputation.participation_score = reputation.participation_score.checked_add(25)
-        .ok_or(GovernanceError::MathOverflow)?;
+    reputation.participation_score = reputation.participation_score.saturating_sub(penalty);
     reputation.last_updated = current_time;
+    breakdown.participation_score = breakdown.participation_score.saturating_sub(penalty);
+    breakdown.last_updated = current_time;
     
-    // Recalculate total score
+    // Recalculate total scores
     reputation.total_score = reputation.investment_score
         .checked_add(reputation.participation_score).ok_or(GovernanceError::MathOverflow)?
         .checked_add(reputation.contribution_score).ok_or(GovernanceError::MathOverflow)?;
+    breakdown.refresh_total_score()
+}
+
+// Sets up a fresh governance-level reputation, existing ones are left untouched
+fn register_reputation_score(reputation: &mut ReputationScore, governance: Pubkey, user: Pubkey, current_time: i64) {
+    if reputation.user == Pubkey::default() {
+        reputation.user = user;
+        reputation.governance = governance;
+        reputation.investment_score = 0;
+        reputation.participation_score = 0;
+        reputation.contribution_score = 0;
+        reputation.total_score = 0;
+        reputation.last_updated = current_time;
+        reputation.decayed_at = current_time;
+        reputation.cap_epoch = 0;
+        reputation.epoch_participation = 0;
+    }
+}
+
+// Sets up a fresh community breakdown, returns whether it was newly registered
+fn register_community_reputation(breakdown: &mut CommunityReputation, community: Pubkey, user: Pubkey, current_time: i64) -> bool {
+    if breakdown.user != Pubkey::default() {
+        return false;
+    }
     
-    // Create activity reward
-    let reward = &mut ctx.accounts.activity_reward;
-    reward.user = ctx.accounts.authority.key();
-    reward.governance = governance.key();
-    reward.reward_type = "proposal".to_string();
-    reward.amount = 50; // Reward amount for creating a proposal
-    reward.timestamp = current_time;
-    reward.is_claimed = false;
-    
-    Ok(())
+    breakdown.community = community;
+    breakdown.user = user;
+    breakdown.investment_score = 0;
+    breakdown.participation_score = 0;
+    breakdown.contribution_score = 0;
+    breakdown.total_score = 0;
+    breakdown.last_updated = current_time;
+    breakdown.decayed_at = current_time;
+    true
 }
 
-pub fn cast_vote(
-    ctx: Context<CastVote>,
-    vote_type: VoteType,
-) -> Result<()> {
-    let proposal = &mut ctx.accounts.proposal;
-    let membership = &ctx.accounts.membership;
+// Adds investment and contribution points to a reputation and to the community it was earned in,
+// returning the investment points actually applied
+fn credit_reputation(
+    reputation: &mut ReputationScore,
+    breakdown: &mut CommunityReputation,
+    investment_delta: i64,
+    contribution_points: u64,
+    current_time: i64,
+) -> Result<i64> {
+    let applied = if investment_delta >= 0 {
+        reputation.investment_score = reputation.investment_score.checked_add(investment_delta as u64)
+            .ok_or(GovernanceError::MathOverflow)?;
+        breakdown.investment_score = breakdown.investment_score.checked_add(investment_delta as u64)
+            .ok_or(GovernanceError::MathOverflow)?;
+        investment_delta
+    } else {
+        // Only points earned in this community are taken, from both levels
+        let loss = investment_delta.unsigned_abs().min(breakdown.investment_score);
+        reputation.investment_score = reputation.investment_score.saturating_sub(loss);
+        breakdown.investment_score -= loss;
+        -(loss as i64)
+    };
     
-    // Check if proposal is active
-    if proposal.status != ProposalStatus::Active {
-        return Err(GovernanceError::InvalidProposalState.into());
-    }
+    reputation.contribution_score = reputation.contribution_score.checked_add(contribution_points)
+        .ok_or(GovernanceError::MathOverflow)?;
+    breakdown.contribution_score = breakdown.contribution_score.checked_add(contribution_points)
+        .ok_or(GovernanceError::MathOverflow)?;
     
-    // Check if voting period is active
-    let current_time = Clock::get()?.unix_timestamp;
-    if current_time > proposal.end_time {
-        return Err(GovernanceError::VotingEnded.into());
-    }
+    reputation.last_updated = current_time;
+    breakdown.last_updated = current_time;
     
-    if current_time < proposal.start_time {
-        return Err(GovernanceError::VotingNotStarted.into());
-    }
+    // Recalculate total scores
+    reputation.total_score = reputation.investment_score
+        .checked_add(reputation.participation_score).ok_or(GovernanceError::MathOverflow)?
+        .checked_add(reputation.contribution_score).ok_or(GovernanceError::MathOverflow)?;
+    breakdown.refresh_total_score()?;
     
-    // Check if user is a member of the community
-    if !membership.is_active || membership.community != proposal.community {
-        return Err(GovernanceError::NotCommunityMember.into());
+    Ok(applied)
+}
+
+// Scales vote weight by the voter's reputation on proposals using reputation-weighted voting
+fn reputation_weighted(proposal: &Proposal, vote_weight: u64, reputation: &ReputationScore) -> Result<u64> {
+    if !proposal.reputation_voting {
+        return Ok(vote_weight);
     }
     
-    // Record vote
-    let vote = &mut ctx.accounts.vote;
-    vote.proposal = proposal.key();
-    vote.voter = ctx.accounts.authority.key();
-    vote.vote_type = vote_type;
-    vote.voting_power = membership.voting_power;
-    vote.vote_time = current_time;
-    
-    // Update proposal vote counts
+    let bonus_bps = reputation.total_score
+        .saturating_mul(REPUTATION_VOTE_BPS_PER_POINT)
+        .min(MAX_REPUTATION_VOTE_BONUS_BPS);
+    let weight = (vote_weight as u128) * (10_000 + bonus_bps as u128) / 10_000;
+    u64::try_from(weight).map_err(|_| GovernanceError::MathOverflow.into())
+}
+
+// Activity reward with the member's vote-escrow boost applied
+fn boosted_reward(base_amount: u64, escrow: Option<&VoteEscrow>, current_time: i64) -> Result<u64> {
+    let boost_bps = escrow.map(|escrow| escrow.reward_boost_bps(current_time)).unwrap_or(0);
+    base_amount
+        .checked_mul(10_000 + boost_bps).ok_or(GovernanceError::MathOverflow)?
+        .checked_div(10_000).ok_or(GovernanceError::MathOverflow.into())
+}
+
+// Adds a For/Against/Abstain vote to a binary proposal
+fn tally_vote(proposal: &mut Proposal, vote_type: VoteType, vote_weight: u64) -> Result<()> {
     match vote_type {
         VoteType::For => {
-            proposal.for_votes = proposal.for_votes.checked_add(membership.voting_power)
+            proposal.for_votes = proposal.for_votes.checked_add(vote_weight)
                 .ok_or(GovernanceError::MathOverflow)?;
         },
         VoteType::Against => {
-            proposal.against_votes = proposal.against_votes.checked_add(membership.voting_power)
+            proposal.against_votes = proposal.against_votes.checked_add(vote_weight)
                 .ok_or(GovernanceError::MathOverflow)?;
         },
         VoteType::Abstain => {
-            proposal.abstain_votes = proposal.abstain_votes.checked_add(membership.voting_power)
+            proposal.abstain_votes = proposal.abstain_votes.checked_add(vote_weight)
                 .ok_or(GovernanceError::MathOverflow)?;
         },
     }
+    Ok(())
+}
+
+// Validates a multi-option ballot and adds it to the per-option tallies
+fn tally_ballot(proposal: &mut Proposal, choices: &[u8], vote_weight: u64) -> Result<()> {
+    // Choices must be distinct, in range, and match the ballot type
+    let option_count = proposal.options.len();
+    let mut seen = [false; MAX_PROPOSAL_OPTIONS];
+    for &choice in choices.iter() {
+        let index = choice as usize;
+        if index >= option_count || seen[index] {
+            return Err(GovernanceError::InvalidBallot.into());
+        }
+        seen[index] = true;
+    }
     
-    // Update reputation score for voting
-    let reputation = &mut ctx.accounts.reputation;
-    reputation.participation_score = reputation.participation_score.checked_add(10)
-        .ok_or(GovernanceError::MathOverflow)?;
-    reputation.last_updated = current_time;
+    let choices_valid = match proposal.ballot_type {
+        BallotType::Binary => false,
+        BallotType::SingleChoice => choices.len() == 1,
+        BallotType::Approval | BallotType::RankedChoice => !choices.is_empty(),
+    };
+    if !choices_valid {
+        return Err(GovernanceError::InvalidBallot.into());
+    }
     
-    // Recalculate total score
-    reputation.total_score = reputation.investment_score
-        .checked_add(reputation.participation_score).ok_or(GovernanceError::MathOverflow)?
-        .checked_add(reputation.contribution_score).ok_or(GovernanceError::MathOverflow)?;
+    for (rank, &choice) in choices.iter().enumerate() {
+        let points = match proposal.ballot_type {
+            // Borda count: first choice scores option_count - 1 times the weight, last ranked scores least
+            BallotType::RankedChoice => vote_weight
+                .checked_mul((option_count - 1 - rank) as u64)
+                .ok_or(GovernanceError::MathOverflow)?,
+            _ => vote_weight,
+        };
+        
+        let option = &mut proposal.options[choice as usize];
+        option.vote_weight = option.vote_weight.checked_add(points)
+            .ok_or(GovernanceError::MathOverflow)?;
+    }
     
-    // Create activity reward
-    let reward = &mut ctx.accounts.activity_reward;
-    reward.user = ctx.accounts.authority.key();
-    reward.governance = ctx.accounts.governance.key();
-    reward.reward_type = "vote".to_string();
-    reward.amount = 10; // Reward amount for voting
-    reward.timestamp = current_time;
-    reward.is_claimed = false;
+    proposal.ballot_weight = proposal.ballot_weight.checked_add(vote_weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    Ok(())
+}
+
+// Fixed-point scale of conviction decay factors
+const CONVICTION_SCALE: u128 = 1_000_000_000_000;
+
+// alpha^steps in CONVICTION_SCALE fixed point, by repeated squaring
+fn conviction_decay_factor(decay_bps: u64, mut steps: u64) -> u128 {
+    let mut base = decay_bps as u128 * CONVICTION_SCALE / 10_000;
+    let mut factor = CONVICTION_SCALE;
+    while steps > 0 {
+        if steps & 1 == 1 {
+            factor = factor * base / CONVICTION_SCALE;
+        }
+        base = base * base / CONVICTION_SCALE;
+        steps >>= 1;
+    }
+    factor
+}
+
+// Brings a funding request's conviction up to date: y = alpha * y + staked for every elapsed step
+fn accrue_conviction(request: &mut FundingRequest, config: &ConvictionConfig, current_time: i64) -> Result<()> {
+    let elapsed_steps = current_time.checked_sub(request.last_updated)
+        .ok_or(GovernanceError::MathOverflow)?
+        / config.step_seconds;
+    if elapsed_steps <= 0 {
+        return Ok(());
+    }
     
+    // Closed form over n steps: alpha^n * y + staked * (1 - alpha^n) / (1 - alpha), alpha < 1
+    let alpha = config.decay_bps as u128 * CONVICTION_SCALE / 10_000;
+    let alpha_n = conviction_decay_factor(config.decay_bps, elapsed_steps as u64);
+    let retained = request.conviction as u128 * alpha_n / CONVICTION_SCALE;
+    let accrued = request.staked_weight as u128 * (CONVICTION_SCALE - alpha_n) / (CONVICTION_SCALE - alpha);
+    let conviction = retained.checked_add(accrued).ok_or(GovernanceError::MathOverflow)?;
+    
+    request.conviction = u64::try_from(conviction).map_err(|_| GovernanceError::MathOverflow)?;
+    request.last_updated = request.last_updated
+        .checked_add(elapsed_steps * config.step_seconds)
+        .ok_or(GovernanceError::MathOverflow)?;
     Ok(())
 }
 
-pub fn finalize_proposal(
-    ctx: Context<FinalizeProposal>,
-) -> Result<()> {
-    let proposal = &mut ctx.accounts.proposal;
-    let governance = &ctx.accounts.governance;
+// Conviction needed to pass: rho * weight / ((1 - alpha) * (beta - requested / balance)^2).
+// Returns None when the request is too large relative to the treasury to ever pass.
+fn conviction_threshold(config: &ConvictionConfig, requested_amount: u64, treasury_balance: u64) -> Option<u128> {
+    if treasury_balance == 0 {
+        return None;
+    }
     
-    // Check if voting period has ended
-    let current_time = Clock::get()?.unix_timestamp;
-    if current_time <= proposal.end_time {
-        return Err(GovernanceError::VotingNotStarted.into());
+    let ratio_bps = requested_amount as u128 * 10_000 / treasury_balance as u128;
+    if ratio_bps >= config.max_ratio_bps as u128 {
+        return None;
     }
     
-    // Check if proposal is still active
-    if proposal.status != ProposalStatus::Active {
-        return Err(GovernanceError::InvalidProposalState.into());
+    let distance_bps = config.max_ratio_bps as u128 - ratio_bps;
+    let effective_weight = config.total_staked.max(config.min_effective_weight) as u128;
+    let numerator = (config.weight_bps as u128)
+        .checked_mul(effective_weight)?
+        .checked_mul(10_000 * 10_000)?;
+    let denominator = (10_000 - config.decay_bps as u128)
+        .checked_mul(distance_bps)?
+        .checked_mul(distance_bps)?;
+    Some(numerator / denominator)
+}
+
+// Outcome of a binary proposal before its deadline: Some(true) if it has tipped to success,
+// Some(false) if it can no longer succeed, None if the outcome is still open
+fn tipped_outcome(proposal: &Proposal, community: &Community, min_vote_threshold: u64) -> Result<Option<bool>> {
+    // Tallies are only bounded for open, binary, linearly weighted votes without reputation multipliers
+    if community.vote_tipping == VoteTipping::Disabled
+        || proposal.ballot_type != BallotType::Binary
+        || proposal.is_secret_ballot
+        || proposal.voting_mode != VotingMode::Linear
+        || proposal.reputation_voting
+    {
+        return Ok(None);
     }
     
-    // Calculate total votes
-    let total_votes = proposal.for_votes
+    let cast_votes = proposal.for_votes
         .checked_add(proposal.against_votes).ok_or(GovernanceError::MathOverflow)?
         .checked_add(proposal.abstain_votes).ok_or(GovernanceError::MathOverflow)?;
+    // Votes are weighted at the proposal start, so is the supply they are bounded by
+    let max_supply = proposal.snapshot_voting_power
+        .checked_add(proposal.snapshot_escrow_weight).ok_or(GovernanceError::MathOverflow)?;
+    let remaining = max_supply.saturating_sub(cast_votes);
     
-    // Check if vote threshold was reached
-    if total_votes < governance.min_vote_threshold {
-        proposal.status = ProposalStatus::Defeated;
-        return Err(GovernanceError::ThresholdNotReached.into());
+    // Defeat is decided once For can no longer overtake Against or quorum is out of reach
+    let max_for = proposal.for_votes.checked_add(remaining).ok_or(GovernanceError::MathOverflow)?;
+    let max_total = cast_votes.checked_add(remaining).ok_or(GovernanceError::MathOverflow)?;
+    if max_for <= proposal.against_votes || max_total < min_vote_threshold {
+        return Ok(Some(false));
     }
     
-    // Determine outcome
-    if proposal.for_votes > proposal.against_votes {
-        proposal.status = ProposalStatus::Succeeded;
-    } else {
-        proposal.status = ProposalStatus::Defeated;
+    let quorum_reached = cast_votes >= min_vote_threshold;
+    let max_against = proposal.against_votes.checked_add(remaining).ok_or(GovernanceError::MathOverflow)?;
+    let passed = match community.vote_tipping {
+        VoteTipping::Strict => quorum_reached && proposal.for_votes > max_against,
+        VoteTipping::Early => quorum_reached && proposal.for_votes > proposal.against_votes,
+        VoteTipping::Disabled => false,
+    };
+    
+    Ok(if passed { Some(true) } else { None })
+}
+
+// Commitment hash binding a secret ballot to its voter
+pub fn ballot_commitment(voter: &Pubkey, vote_type: VoteType, choices: &[u8], salt: &[u8; 32]) -> [u8; 32] {
+    hashv(&[voter.as_ref(), &[vote_type as u8], choices, salt]).to_bytes()
+}
+
+// Message a member signs off chain to vote on a binary proposal through a relayer
+pub fn signed_vote_message(proposal: &Pubkey, vote_type: VoteType) -> Vec<u8> {
+    let mut message = SIGNED_VOTE_DOMAIN.to_vec();
+    message.extend_from_slice(proposal.as_ref());
+    message.push(vote_type as u8);
+    message
+}
+
+// Signers and messages of the signatures checked by an ed25519 precompile instruction
+fn verified_ed25519_signatures(instruction: &Instruction) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    if instruction.program_id != ed25519_program::ID {
+        return Err(GovernanceError::InvalidSignedVote.into());
     }
     
-    // Update proposer's reputation if proposal succeeded
-    if proposal.status == ProposalStatus::Succeeded {
-        let reputation = &mut ctx.accounts.proposer_reputation;
-        reputation.participation_score = reputation.participation_score.checked_add(50)
-            .ok_or(GovernanceError::MathOverflow)?;
-            
-        // Extra points for investment proposals
-        if proposal.is_investment_proposal {
-            reputation.investment_score = reputation.investment_score.checked_add(25)
-                .ok_or(GovernanceError::MathOverflow)?;
+    let data = &instruction.data;
+    let count = *data.first().ok_or(GovernanceError::InvalidSignedVote)? as usize;
+    let mut signatures = Vec::with_capacity(count);
+    for index in 0..count {
+        // Offsets: signature, signature ix, public key, public key ix, message, message size, message ix
+        let start = 2 + index * 14;
+        let offsets = data.get(start..start + 14).ok_or(GovernanceError::InvalidSignedVote)?;
+        let read = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+        
+        // Only data embedded in the precompile instruction itself is trusted
+        if read(2) != u16::MAX || read(6) != u16::MAX || read(12) != u16::MAX {
+            return Err(GovernanceError::InvalidSignedVote.into());
         }
         
-        reputation.last_updated = current_time;
+        let public_key_offset = read(4) as usize;
+        let message_offset = read(8) as usize;
+        let message_end = message_offset + read(10) as usize;
+        let public_key = data.get(public_key_offset..public_key_offset + 32).ok_or(GovernanceError::InvalidSignedVote)?;
+        let message = data.get(message_offset..message_end).ok_or(GovernanceError::InvalidSignedVote)?;
         
-        // Recalculate total score
-        reputation.total_score = reputation.investment_score
-            .checked_add(reputation.participation_score).ok_or(GovernanceError::MathOverflow)?
-            .checked_add(reputation.contribution_score).ok_or(GovernanceError::MathOverflow)?;
+        signatures.push((Pubkey::try_from(public_key).map_err(|_| GovernanceError::InvalidSignedVote)?, message.to_vec()));
     }
     
-    Ok(())
+    Ok(signatures)
 }
 
-pub fn execute_proposal(
-    ctx: Context<ExecuteProposal>,
-) -> Result<()> {
-    let proposal = &mut ctx.accounts.proposal;
-    
-    // Check if proposal succeeded
-    if proposal.status != ProposalStatus::Succeeded {
-        return Err(GovernanceError::InvalidProposalState.into());
+pub fn initialize_governance(
+    ctx: Context<InitializeGovernance>,
+    name: String,
+    min_vote_threshold: u64,
+    voting_period: i64,
+    execution_delay: i64,
+    execution_window: i64,
+    reward_epoch_duration: i64,
+) -> Result<()> {
+    if execution_delay < 0 || execution_window < 0 || reward_epoch_duration <= 0 {
+        return Err(GovernanceError::InvalidGovernanceConfig.into());
     }
     
-    // In a real implementation, we would execute the stored instructions here
-    // For this sample, we'll just mark it as executed
+    let governance = &mut ctx.accounts.governance;
+    governance.authority = ctx.accounts.authority.key();
+    governance.name = name;
+    governance.min_vote_threshold = min_vote_threshold;
+    governance.voting_period = voting_period;
+    governance.proposal_count = 0;
+    governance.total_communities = 0;
+    governance.is_active = true;
+    governance.execution_delay = execution_delay;
+    governance.execution_window = execution_window;
+    governance.reward_epoch_duration = reward_epoch_duration;
+    governance.reputation_decay_bps = DEFAULT_REPUTATION_DECAY_BPS;
+    governance.participation_cap = DEFAULT_PARTICIPATION_CAP;
+    governance.defeat_penalty = DEFAULT_DEFEAT_PENALTY;
+    governance.veto_penalty = DEFAULT_VETO_PENALTY;
+    governance.reputation_voting = false;
+    governance.config_version = 0;
+    governance.recognized_communities = 0;
+    Ok(())
+}
+
+// Applies the amended parameters to the governance config
+fn apply_config_update(governance: &mut GovernanceConfig, update: &GovernanceConfigUpdate) -> Result<()> {
+    if matches!(update.voting_period, Some(period) if period <= 0)
+        || matches!(update.reputation_decay_bps, Some(decay_bps) if decay_bps > 10_000)
+    {
+        return Err(GovernanceError::InvalidGovernanceConfig.into());
+    }
     
-    proposal.status = ProposalStatus::Executed;
-    proposal.executed_at = Some(Clock::get()?.unix_timestamp);
+    if let Some(min_vote_threshold) = update.min_vote_threshold {
+        governance.min_vote_threshold = min_vote_threshold;
+    }
+    if let Some(voting_period) = update.voting_period {
+        governance.voting_period = voting_period;
+    }
+    if let Some(is_active) = update.is_active {
+        governance.is_active = is_active;
+    }
+    if let Some(reputation_decay_bps) = update.reputation_decay_bps {
+        governance.reputation_decay_bps = reputation_decay_bps;
+    }
+    if let Some(participation_cap) = update.participation_cap {
+        governance.participation_cap = participation_cap;
+    }
+    if let Some(defeat_penalty) = update.defeat_penalty {
+        governance.defeat_penalty = defeat_penalty;
+    }
+    if let Some(veto_penalty) = update.veto_penalty {
+        governance.veto_penalty = veto_penalty;
+    }
+    if let Some(reputation_voting) = update.reputation_voting {
+        governance.reputation_voting = reputation_voting;
+    }
+    Ok(())
+}
+
+// Applies an amendment once a majority of the recognized communities approved it through executed proposals
+pub fn update_governance_config(
+    ctx: Context<UpdateGovernanceConfig>,
+) -> Result<()> {
+    let governance = &mut ctx.accounts.governance;
+    let amendment = &ctx.accounts.config_amendment;
     
-    // Update proposer's reputation for successful execution
-    let reputation = &mut ctx.accounts.proposer_reputation;
-    reputation.participation_score = reputation.participation_score.checked_add(25)
+    // Anyone can create communities, so only those the authority recognized count towards the quorum
+    let approvals = amendment.approvals as u64;
+    if approvals.checked_mul(2).ok_or(GovernanceError::MathOverflow)? <= governance.recognized_communities {
+        return Err(GovernanceError::AmendmentQuorumNotReached.into());
+    }
+    
+    apply_config_update(governance, &amendment.update)?;
+    
+    // Outstanding approvals were given for the old config and stop counting
+    governance.config_version = governance.config_version.checked_add(1)
         .ok_or(GovernanceError::MathOverflow)?;
-    reputation.last_updated = Clock::get()?.unix_timestamp;
     
-    // Recalculate total score
-    reputation.total_score = reputation.investment_score
-        .checked_add(reputation.participation_score).ok_or(GovernanceError::MathOverflow)?
-        .checked_add(reputation.contribution_score).ok_or(GovernanceError::MathOverflow)?;
+    Ok(())
+}
+
+// Grants or revokes a community's say in config amendments, only active communities can be recognized
+pub fn set_community_recognition(
+    ctx: Context<SetCommunityRecognition>,
+    recognized: bool,
+) -> Result<()> {
+    let governance = &mut ctx.accounts.governance;
+    let community = &mut ctx.accounts.community;
+    
+    if recognized == community.recognized {
+        return Ok(());
+    }
+    if recognized && !community.is_active {
+        return Err(GovernanceError::CommunityInactive.into());
+    }
+    
+    community.recognized = recognized;
+    if recognized {
+        governance.recognized_communities = governance.recognized_communities.checked_add(1)
+            .ok_or(GovernanceError::MathOverflow)?;
+    } else {
+        governance.recognized_communities = governance.recognized_communities.checked_sub(1)
+            .ok_or(GovernanceError::MathOverflow)?;
+        
+        // Outstanding amendments may hold the community's approval, so they stop counting
+        governance.config_version = governance.config_version.checked_add(1)
+            .ok_or(GovernanceError::MathOverflow)?;
+    }
     
     Ok(())
 }
 
-pub fn create_faction(
-    ctx: Context<CreateFaction>,
+// Applies pending decay so reputation gates and voting read a current score, callable by anyone
+pub fn refresh_reputation(
+    ctx: Context<RefreshReputation>,
+) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    decay_reputation(&mut ctx.accounts.reputation, &ctx.accounts.governance, current_time)
+}
+
+pub fn create_community(
+    ctx: Context<CreateCommunity>,
     name: String,
     description: String,
-    investment_strategy: String,
+    veto_window: i64,
+    voting_mode: VotingMode,
+    reveal_period: i64,
+    vote_tipping: VoteTipping,
 ) -> Result<()> {
-    let faction = &mut ctx.accounts.faction;
+    // Vetoes run forward from the end of voting
+    if veto_window < 0 {
+        return Err(GovernanceError::InvalidGovernanceConfig.into());
+    }
+    if reveal_period < 0 {
+        return Err(GovernanceError::InvalidGovernanceConfig.into());
+    }
     
-    faction.governance = ctx.accounts.governance.key();
-    faction.name = name;
-    faction.description = description;
-    faction.founder = ctx.accounts.authority.key();
-    faction.member_count = 1; // Founder is the first member
-    faction.creation_time = Clock::get()?.unix_timestamp;
-    faction.investment_strategy = investment_strategy;
-    faction.is_active = true;
+    let governance = &mut ctx.accounts.governance;
+    let community = &mut ctx.accounts.community;
     
-    // Create membership for faction founder
+    community.governance = governance.key();
+    community.name = name;
+    community.description = description;
+    community.authority = ctx.accounts.authority.key();
+    community.member_count = 1; // Creator is the first member
+    community.proposal_count = 0;
+    community.creation_time = Clock::get()?.unix_timestamp;
+    community.is_active = true;
+    community.veto_window = veto_window;
+    community.voting_mode = voting_mode;
+    community.voting_mint = None;
+    community.reveal_period = reveal_period;
+    community.max_escrow_lock = 0;
+    community.vote_tipping = vote_tipping;
+    community.total_voting_power = 100; // Creator's admin voting power
+    community.total_escrow_weight = 0;
+    community.proposal_deposit = 0;
+    community.deposit_mint = None;
+    community.membership_requirement = MembershipRequirement::None;
+    community.join_policy = JoinPolicy::Open;
+    community.council_threshold = 0;
+    community.recognized = false;
+    
+    // Create membership for community creator
     let membership = &mut ctx.accounts.membership;
-    membership.faction = faction.key();
+    membership.community = community.key();
     membership.user = ctx.accounts.authority.key();
+    membership.role = MemberRole::Admin;
+    membership.voting_power = 100; // Admin starts with high voting power
     membership.join_time = Clock::get()?.unix_timestamp;
+    membership.reputation_score = 100; // Initial reputation score
     membership.is_active = true;
+    membership.deposited_amount = 0;
+    membership.deposit_locked_until = 0;
+    membership.conviction_staked = 0;
+    membership.delegated_to = None;
+    membership.qualifying_mint = None;
+    membership.removed = false;
+    
+    // Increment total communities counter
+    governance.total_communities = governance.total_communities.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    // The creator gets a reputation like any joining member
+    let current_time = Clock::get()?.unix_timestamp;
+    checkpoint_membership(&mut ctx.accounts.power_checkpoints, membership, current_time);
+    fit_checkpoints(&ctx.accounts.power_checkpoints, ctx.accounts.authority.to_account_info(), ctx.accounts.system_program.to_account_info())?;
+    let reputation = &mut ctx.accounts.reputation;
+    let breakdown = &mut ctx.accounts.community_reputation;
+    register_reputation_score(reputation, governance.key(), ctx.accounts.authority.key(), current_time);
+    register_community_reputation(breakdown, community.key(), ctx.accounts.authority.key(), current_time);
+    award_participation(reputation, breakdown, governance, 10, current_time)?;
     
     Ok(())
 }
 
-pub fn join_faction(
-    ctx: Context<JoinFaction>,
+// Registers a member's governance reputation and their breakdown for the community
+pub fn register_reputation(
+    ctx: Context<RegisterReputation>,
 ) -> Result<()> {
-    let faction = &mut ctx.accounts.faction;
-    let membership = &mut ctx.accounts.membership;
+    let current_time = Clock::get()?.unix_timestamp;
+    register_reputation_score(&mut ctx.accounts.reputation, ctx.accounts.governance.key(), ctx.accounts.authority.key(), current_time);
+    register_community_reputation(&mut ctx.accounts.community_reputation, ctx.accounts.community.key(), ctx.accounts.authority.key(), current_time);
     
-    membership.faction = faction.key();
-    membership.user = ctx.accounts.user.key();
-    membership.join_time = Clock::get()?.unix_timestamp;
-    membership.is_active = true;
+    Ok(())
+}
+
+// Returns a user's reputation, with pending decay applied, for other programs to read
+pub fn get_reputation(
+    ctx: Context<GetReputation>,
+) -> Result<ReputationView> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let mut reputation = (*ctx.accounts.reputation).clone();
+    decay_reputation(&mut reputation, &ctx.accounts.governance, current_time)?;
     
-    // Increment faction member count
-    faction.member_count = faction.member_count.checked_add(1)
-        .ok_or(GovernanceError::MathOverflow)?;
+    // Breakdowns are only reported for communities of this governance
+    let breakdown = match (ctx.accounts.community_reputation.as_ref(), ctx.accounts.community.as_ref()) {
+        (Some(breakdown), Some(community)) if breakdown.community == community.key() => {
+            let mut breakdown = (**breakdown).clone();
+            decay_breakdown(&mut breakdown, &ctx.accounts.governance, current_time)?;
+            Some(breakdown)
+        },
+        (None, _) => None,
+        _ => return Err(GovernanceError::InvalidCommunityReputation.into()),
+    };
+    let breakdown = breakdown.as_ref();
+    Ok(ReputationView {
+        user: reputation.user,
+        governance: reputation.governance,
+        investment_score: reputation.investment_score,
+        participation_score: reputation.participation_score,
+        contribution_score: reputation.contribution_score,
+        total_score: reputation.total_score,
+        community: breakdown.map(|breakdown| breakdown.community),
+        community_score: breakdown.map_or(0, |breakdown| breakdown.total_score),
+    })
+}
+
+// Reads a borsh length prefix from token metadata
+fn read_metadata_len(data: &[u8], offset: usize) -> Option<usize> {
+    let bytes: [u8; 4] = data.get(offset..offset + 4)?.try_into().ok()?;
+    Some(u32::from_le_bytes(bytes) as usize)
+}
+
+// Whether token metadata lists the creator as a verified creator
+fn has_verified_creator(data: &[u8], creator: &Pubkey) -> Option<bool> {
+    // Key, update authority and mint precede the name, symbol and uri strings
+    let mut offset = 1 + 32 + 32;
+    for _ in 0..3 {
+        offset += 4 + read_metadata_len(data, offset)?;
+    }
     
-    Ok(())
+    // Seller fee basis points, then the optional creators list
+    offset += 2;
+    if *data.get(offset)? == 0 {
+        return Some(false);
+    }
+    offset += 1;
+    
+    let count = read_metadata_len(data, offset)?;
+    offset += 4;
+    for index in 0..count {
+        let start = offset + index * 34;
+        let entry = data.get(start..start + 34)?;
+        if entry[..32] == creator.as_ref()[..] && entry[32] == 1 {
+            return Some(true);
+        }
+    }
+    
+    Some(false)
 }
 
-pub fn create_investment_strategy(
-    ctx: Context<CreateInvestmentStrategy>,
-    name: String,
-    description: String,
-    risk_level: u8,
-    target_collections: Vec<Pubkey>,
-    allocation_percentages: Vec<u8>,
-) -> Result<()> {
-    // Validate risk level
-    if risk_level == 0 || risk_level > 5 {
-        return Err(GovernanceError::InvalidInvestmentAllocation.into());
+// Whether a user meets a community's entry requirement, missing or mismatched proof accounts are an error.
+// A qualifying mint pins collection proofs to the NFT a member joined with.
+#[allow(clippy::too_many_arguments)]
+fn meets_membership_requirement(
+    requirement: &MembershipRequirement,
+    user: &Pubkey,
+    qualifying_mint: Option<Pubkey>,
+    reputation: &ReputationScore,
+    collection: Option<&Account<CollectionRating>>,
+    vault: Option<&Account<FractionalVault>>,
+    token_account: Option<&Account<anchor_spl::token::TokenAccount>>,
+    nft_metadata: Option<&UncheckedAccount>,
+) -> Result<bool> {
+    match *requirement {
+        MembershipRequirement::None => Ok(true),
+        // A reputation created by this join has no score yet
+        MembershipRequirement::MinReputation { min_score } => {
+            Ok(reputation.user == *user && reputation.total_score >= min_score)
+        },
+        MembershipRequirement::FractionBalance { vault: required_vault, min_amount } => {
+            let (Some(vault), Some(token_account)) = (vault, token_account) else {
+                return Err(GovernanceError::InvalidRequirementAccount.into());
+            };
+            
+            // Balances are read from the user's associated token account
+            let expected_account = anchor_spl::associated_token::get_associated_token_address(user, &vault.token_mint);
+            if vault.key() != required_vault || token_account.key() != expected_account {
+                return Err(GovernanceError::InvalidRequirementAccount.into());
+            }
+            
+            Ok(token_account.owner == *user && token_account.amount >= min_amount)
+        },
+        MembershipRequirement::CollectionNft { collection: required_collection } => {
+            let (Some(collection), Some(token_account), Some(nft_metadata)) = (collection, token_account, nft_metadata) else {
+                return Err(GovernanceError::InvalidRequirementAccount.into());
+            };
+            
+            let expected_account = anchor_spl::associated_token::get_associated_token_address(user, &token_account.mint);
+            let (expected_metadata, _) = Pubkey::find_program_address(
+                &[b"metadata".as_ref(), TOKEN_METADATA_PROGRAM_ID.as_ref(), token_account.mint.as_ref()],
+                &TOKEN_METADATA_PROGRAM_ID,
+            );
+            if collection.key() != required_collection
+                || token_account.key() != expected_account
+                || nft_metadata.key() != expected_metadata
+                || *nft_metadata.owner != TOKEN_METADATA_PROGRAM_ID
+                || matches!(qualifying_mint, Some(mint) if mint != token_account.mint)
+            {
+                return Err(GovernanceError::InvalidRequirementAccount.into());
+            }
+            
+            // The proof must be an NFT of the required collection, whatever its balance
+            let data = nft_metadata.try_borrow_data()?;
+            if has_verified_creator(&data, &collection.creator) != Some(true) {
+                return Err(GovernanceError::InvalidRequirementAccount.into());
+            }
+            
+            Ok(collection.is_verified && token_account.owner == *user && token_account.amount == 1)
+        },
     }
+}
+
+pub fn join_community(
+    ctx: Context<JoinCommunity>,
+) -> Result<()> {
+    let community = &mut ctx.accounts.community;
+    let membership = &mut ctx.accounts.membership;
+    let reputation = &mut ctx.accounts.reputation;
     
-    // Validate allocation percentages
-    if target_collections.len() != allocation_percentages.len() {
-        return Err(GovernanceError::InvalidInvestmentAllocation.into());
+    // Verify the community's entry requirement against the user's standing before joining
+    // Members deactivated by a requirement re-check rejoin on their existing membership
+    if membership.is_active {
+        return Err(GovernanceError::AlreadyMember.into());
     }
     
-    // Ensure allocation percentages sum to 100
-    let total_percentage: u8 = allocation_percentages.iter().sum();
-    if total_percentage != 100 {
-        return Err(GovernanceError::InvalidInvestmentAllocation.into());
+    if membership.removed {
+        return Err(GovernanceError::MemberRemoved.into());
     }
     
-    let strategy = &mut ctx.accounts.strategy;
+    // Reputation requirements are checked against the score left after pending decay
     let current_time = Clock::get()?.unix_timestamp;
+    decay_reputation(reputation, &ctx.accounts.governance, current_time)?;
     
-    strategy.community = ctx.accounts.community.key();
-    strategy.name = name;
-    strategy.description = description;
-    strategy.creator = ctx.accounts.authority.key();
-    strategy.risk_level = risk_level;
-    strategy.target_collections = target_collections;
-    strategy.allocation_percentages = allocation_percentages;
-    strategy.creation_time = current_time;
-    strategy.last_updated = current_time;
-    strategy.performance_score = 0; // Initial performance score
-    strategy.is_active = true;
+    let qualifies = meets_membership_requirement(
+        &community.membership_requirement,
+        &ctx.accounts.user.key(),
+        None,
+        reputation,
+        ctx.accounts.requirement_collection.as_ref(),
+        ctx.accounts.requirement_vault.as_ref(),
+        ctx.accounts.requirement_token_account.as_ref(),
+        ctx.accounts.nft_metadata.as_ref(),
+    )?;
+    if !qualifies {
+        return Err(GovernanceError::MembershipRequirementNotMet.into());
+    }
     
-    // Update reputation for strategy creation
-    let reputation = &mut ctx.accounts.reputation;
-    reputation.investment_score = reputation.investment_score.checked_add(30)
+    // Consume the invite or approved application the join policy calls for
+    match community.join_policy {
+        JoinPolicy::Open => {},
+        JoinPolicy::InviteOnly => {
+            let (Some(invite), Some(inviter)) = (ctx.accounts.invite.as_ref(), ctx.accounts.inviter.as_ref()) else {
+                return Err(GovernanceError::InvalidInvite.into());
+            };
+            
+            if invite.inviter != inviter.key() || (invite.expires_at > 0 && current_time > invite.expires_at) {
+                return Err(GovernanceError::InvalidInvite.into());
+            }
+            
+            invite.close(inviter.to_account_info())?;
+        },
+        JoinPolicy::Application => {
+            let Some(application) = ctx.accounts.application.as_ref() else {
+                return Err(GovernanceError::ApplicationNotApproved.into());
+            };
+            
+            if application.status != ApplicationStatus::Approved {
+                return Err(GovernanceError::ApplicationNotApproved.into());
+            }
+            
+            application.close(ctx.accounts.user.to_account_info())?;
+        },
+    }
+    
+    membership.community = community.key();
+    membership.user = ctx.accounts.user.key();
+    membership.role = MemberRole::Member;
+    membership.voting_power = 10; // New members start with base voting power
+    membership.join_time = current_time;
+    membership.reputation_score = 10; // Initial reputation score
+    membership.is_active = true;
+    // Deposits, stakes and delegations of a returning member are still outstanding and carry over
+    membership.qualifying_mint = match community.membership_requirement {
+        MembershipRequirement::CollectionNft { .. } => ctx.accounts.requirement_token_account.as_ref().map(|account| account.mint),
+        _ => None,
+    };
+    
+    // Increment community member count
+    community.member_count = community.member_count.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    community.total_voting_power = community.total_voting_power.checked_add(membership.voting_power)
         .ok_or(GovernanceError::MathOverflow)?;
-    reputation.last_updated = current_time;
     
-    // Recalculate total score
-    reputation.total_score = reputation.investment_score
-        .checked_add(reputation.participation_score).ok_or(GovernanceError::MathOverflow)?
-        .checked_add(reputation.contribution_score).ok_or(GovernanceError::MathOverflow)?;
+    // Register reputation for new members, members of other communities keep theirs
+    checkpoint_membership(&mut ctx.accounts.power_checkpoints, membership, current_time);
+    fit_checkpoints(&ctx.accounts.power_checkpoints, ctx.accounts.user.to_account_info(), ctx.accounts.system_program.to_account_info())?;
+    register_reputation_score(reputation, community.governance, ctx.accounts.user.key(), current_time);
+    
+    // First-time members of this community earn the joining points
+    let breakdown = &mut ctx.accounts.community_reputation;
+    if register_community_reputation(breakdown, community.key(), ctx.accounts.user.key(), current_time) {
+        award_participation(reputation, breakdown, &ctx.accounts.governance, 10, current_time)?;
+    }
     
     Ok(())
 }
 
-// Account contexts for the instructions
-
-#[derive(Accounts)]
-#[instruction(name: String, min_vote_threshold: u64, voting_period: i64)]
-pub struct InitializeGovernance<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + 32 + 4 + name.len() + 8 + 8 + 8 + 8 + 1,
-        seeds = [b"governance".as_ref(), authority.key().as_ref()],
-        bump
-    )]
-    pub governance: Account<'info, GovernanceConfig>,
+pub fn set_join_policy(
+    ctx: Context<UpdateMembershipPolicy>,
+    join_policy: JoinPolicy,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::ManageMembers) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    ctx.accounts.community.join_policy = join_policy;
     
-    pub system_program: Program<'info, System>,
+    Ok(())
 }
 
-#[derive(Accounts)]
-#[instruction(name: String, description: String)]
-pub struct CreateCommunity<'info> {
-    #[account(
-        mut,
-        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
-        bump,
-        constraint = governance.is_active
-    )]
-    pub governance: Account<'info, GovernanceConfig>,
-    
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + 32 + 4 + name.len() + 4 + description.len() + 32 + 8 + 8 + 8 + 1,
-        seeds = [b"community".as_ref(), governance.key().as_ref(), name.as_bytes()],
-        bump
-    )]
-    pub community: Account<'info, Community>,
+pub fn issue_invite(
+    ctx: Context<IssueInvite>,
+    invitee: Pubkey,
+    expires_at: i64,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::ManageMembers) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
     
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + 32 + 32 + 4 + 10 + 8 + 8 + 8 + 1, // "admin".len() = 5
-        seeds = [b"membership".as_ref(), community.key().as_ref(), authority.key().as_ref()],
-        bump
-    )]
-    pub membership: Account<'info, Membership>,
+    let current_time = Clock::get()?.unix_timestamp;
+    if expires_at != 0 && expires_at <= current_time {
+        return Err(GovernanceError::InvalidInvite.into());
+    }
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    let invite = &mut ctx.accounts.invite;
+    invite.community = ctx.accounts.community.key();
+    invite.invitee = invitee;
+    invite.inviter = ctx.accounts.authority.key();
+    invite.created_at = current_time;
+    invite.expires_at = expires_at;
     
-    pub system_program: Program<'info, System>,
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct JoinCommunity<'info> {
-    #[account(
+pub fn revoke_invite(
+    ctx: Context<RevokeInvite>,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::ManageMembers) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    // Invite account is closed to its inviter by the account constraint
+    Ok(())
+}
+
+pub fn apply_to_community(
+    ctx: Context<ApplyToCommunity>,
+    message: String,
+) -> Result<()> {
+    let application = &mut ctx.accounts.application;
+    application.community = ctx.accounts.community.key();
+    application.applicant = ctx.accounts.applicant.key();
+    application.message = message;
+    application.status = ApplicationStatus::Pending;
+    application.submitted_at = Clock::get()?.unix_timestamp;
+    application.reviewed_by = None;
+    application.reviewed_at = None;
+    
+    Ok(())
+}
+
+pub fn review_application(
+    ctx: Context<ReviewApplication>,
+    approve: bool,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::ManageMembers) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    let application = &mut ctx.accounts.application;
+    if application.status != ApplicationStatus::Pending {
+        return Err(GovernanceError::ApplicationAlreadyReviewed.into());
+    }
+    
+    application.status = if approve { ApplicationStatus::Approved } else { ApplicationStatus::Rejected };
+    application.reviewed_by = Some(ctx.accounts.authority.key());
+    application.reviewed_at = Some(Clock::get()?.unix_timestamp);
+    
+    Ok(())
+}
+
+pub fn withdraw_application(
+    _ctx: Context<WithdrawApplication>,
+) -> Result<()> {
+    // Application account is closed to the applicant by the account constraint
+    Ok(())
+}
+
+pub fn set_membership_requirement(
+    ctx: Context<UpdateMembershipPolicy>,
+    requirement: MembershipRequirement,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::ManageMembers) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    ctx.accounts.community.membership_requirement = requirement;
+    
+    Ok(())
+}
+
+pub fn recheck_membership(
+    ctx: Context<RecheckMembership>,
+) -> Result<()> {
+    let community = &mut ctx.accounts.community;
+    let member = &mut ctx.accounts.member_membership;
+    
+    // The creator founded the community before any requirement applied
+    if member.user == community.authority {
+        return Err(GovernanceError::CommunityAuthorityProtected.into());
+    }
+    
+    // Collection proofs must be for the NFT the member joined with, members who joined before the
+    // requirement have none on record and cannot be re-checked against a collection
+    let qualifying_mint = match community.membership_requirement {
+        MembershipRequirement::CollectionNft { .. } => {
+            Some(member.qualifying_mint.ok_or(GovernanceError::InvalidRequirementAccount)?)
+        },
+        _ => None,
+    };
+    
+    let mut reputation = (*ctx.accounts.reputation).clone();
+    decay_reputation(&mut reputation, &ctx.accounts.governance, Clock::get()?.unix_timestamp)?;
+    
+    let qualifies = meets_membership_requirement(
+        &community.membership_requirement,
+        &member.user,
+        qualifying_mint,
+        &reputation,
+        ctx.accounts.requirement_collection.as_ref(),
+        ctx.accounts.requirement_vault.as_ref(),
+        ctx.accounts.requirement_token_account.as_ref(),
+        ctx.accounts.nft_metadata.as_ref(),
+    )?;
+    
+    // Members who no longer qualify are deactivated, but may rejoin once they qualify again
+    if !qualifies {
+        member.is_active = false;
+        member.role = MemberRole::Member;
+        
+        community.member_count = community.member_count.checked_sub(1)
+            .ok_or(GovernanceError::MathOverflow)?;
+        community.total_voting_power = community.total_voting_power.checked_sub(member.voting_power)
+            .ok_or(GovernanceError::MathOverflow)?;
+    }
+    
+    Ok(())
+}
+
+pub fn create_voting_vault(
+    ctx: Context<CreateVotingVault>,
+    max_escrow_lock: i64,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::ManageTreasury) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    if max_escrow_lock <= 0 {
+        return Err(GovernanceError::InvalidLockDuration.into());
+    }
+    
+    let community = &mut ctx.accounts.community;
+    if community.voting_mint.is_some() {
+        return Err(GovernanceError::InvalidVotingMint.into());
+    }
+    
+    community.voting_mint = Some(ctx.accounts.voting_mint.key());
+    community.max_escrow_lock = max_escrow_lock;
+    
+    Ok(())
+}
+
+pub fn set_proposal_deposit(
+    ctx: Context<SetProposalDeposit>,
+    amount: u64,
+    deposit_mint: Option<Pubkey>,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::ManageTreasury) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    // Applies to proposals created from now on, existing ones keep their deposit terms
+    let community = &mut ctx.accounts.community;
+    community.proposal_deposit = amount;
+    community.deposit_mint = deposit_mint;
+    
+    Ok(())
+}
+
+pub fn deposit_voting_tokens(
+    ctx: Context<DepositVotingTokens>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Err(GovernanceError::InvalidAmount.into());
+    }
+    
+    // Transfer tokens from member to the community voting vault
+    let transfer_ix = anchor_spl::token::Transfer {
+        from: ctx.accounts.user_token_account.to_account_info(),
+        to: ctx.accounts.voting_vault.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+    };
+    
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+    );
+    
+    anchor_spl::token::transfer(cpi_ctx, amount)?;
+    
+    let membership = &mut ctx.accounts.membership;
+    membership.deposited_amount = membership.deposited_amount.checked_add(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    checkpoint_membership(&mut ctx.accounts.power_checkpoints, membership, Clock::get()?.unix_timestamp);
+    fit_checkpoints(&ctx.accounts.power_checkpoints, ctx.accounts.authority.to_account_info(), ctx.accounts.system_program.to_account_info())?;
+    
+    Ok(())
+}
+
+pub fn withdraw_voting_tokens(
+    ctx: Context<WithdrawVotingTokens>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Err(GovernanceError::InvalidAmount.into());
+    }
+    
+    // Deposits cannot move while they back an ongoing vote
+    if Clock::get()?.unix_timestamp <= ctx.accounts.membership.deposit_locked_until {
+        return Err(GovernanceError::DepositLocked.into());
+    }
+    
+    let membership = &mut ctx.accounts.membership;
+    membership.deposited_amount = membership.deposited_amount.checked_sub(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    checkpoint_membership(&mut ctx.accounts.power_checkpoints, membership, Clock::get()?.unix_timestamp);
+    fit_checkpoints(&ctx.accounts.power_checkpoints, ctx.accounts.authority.to_account_info(), ctx.accounts.system_program.to_account_info())?;
+    
+    // Return tokens from the vault, signed by the vault authority PDA
+    let transfer_ix = anchor_spl::token::Transfer {
+        from: ctx.accounts.voting_vault.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    
+    let community_key = ctx.accounts.community.key();
+    let vault_authority_seeds = &[
+        b"vault_authority".as_ref(),
+        community_key.as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    
+    let signer_seeds = &[&vault_authority_seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+        signer_seeds,
+    );
+    
+    anchor_spl::token::transfer(cpi_ctx, amount)?;
+    
+    Ok(())
+}
+
+pub fn lock_escrow_tokens(
+    ctx: Context<LockEscrowTokens>,
+    amount: u64,
+    unlock_time: i64,
+) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let max_lock = ctx.accounts.community.max_escrow_lock;
+    let escrow = &ctx.accounts.vote_escrow;
+    
+    // New locks need tokens, existing locks may just extend their unlock time
+    if amount == 0 && escrow.amount == 0 {
+        return Err(GovernanceError::InvalidAmount.into());
+    }
+    
+    // Locks can only be extended, up to the community maximum
+    let latest_unlock = current_time.checked_add(max_lock).ok_or(GovernanceError::MathOverflow)?;
+    if unlock_time <= current_time || unlock_time > latest_unlock || unlock_time < escrow.unlock_time {
+        return Err(GovernanceError::InvalidLockDuration.into());
+    }
+    
+    if amount > 0 {
+        let transfer_ix = anchor_spl::token::Transfer {
+            from: ctx.accounts.user_token_account.to_account_info(),
+            to: ctx.accounts.voting_vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_ix,
+        );
+        
+        anchor_spl::token::transfer(cpi_ctx, amount)?;
+    }
+    
+    let escrow = &mut ctx.accounts.vote_escrow;
+    if escrow.amount == 0 {
+        escrow.lock_start = current_time;
+    }
+    
+    // Track the community-wide bound on escrow power in whole tokens
+    let scale = 10u64.checked_pow(ctx.accounts.voting_mint.decimals as u32).ok_or(GovernanceError::MathOverflow)?;
+    let previous_weight = escrow.amount / scale;
+    
+    escrow.community = ctx.accounts.community.key();
+    escrow.user = ctx.accounts.authority.key();
+    escrow.amount = escrow.amount.checked_add(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    let community = &mut ctx.accounts.community;
+    community.total_escrow_weight = community.total_escrow_weight
+        .checked_add(escrow.amount / scale - previous_weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    escrow.decimals = ctx.accounts.voting_mint.decimals;
+    escrow.unlock_time = unlock_time;
+    escrow.max_lock_duration = max_lock;
+    
+    let checkpoints = &mut ctx.accounts.power_checkpoints;
+    checkpoint_membership(checkpoints, &ctx.accounts.membership, current_time);
+    checkpoint_escrow(checkpoints, escrow.community, escrow.user, Some(escrow), current_time);
+    fit_checkpoints(&ctx.accounts.power_checkpoints, ctx.accounts.authority.to_account_info(), ctx.accounts.system_program.to_account_info())?;
+    
+    Ok(())
+}
+
+pub fn withdraw_escrow_tokens(
+    ctx: Context<WithdrawEscrowTokens>,
+) -> Result<()> {
+    let escrow = &ctx.accounts.vote_escrow;
+    if Clock::get()?.unix_timestamp < escrow.unlock_time {
+        return Err(GovernanceError::EscrowLocked.into());
+    }
+    
+    // Return locked tokens from the vault, signed by the vault authority PDA
+    let transfer_ix = anchor_spl::token::Transfer {
+        from: ctx.accounts.voting_vault.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    
+    let community_key = ctx.accounts.community.key();
+    let vault_authority_seeds = &[
+        b"vault_authority".as_ref(),
+        community_key.as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    
+    let signer_seeds = &[&vault_authority_seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+        signer_seeds,
+    );
+    
+    // Escrow account is closed to the user by the account constraint
+    anchor_spl::token::transfer(cpi_ctx, escrow.amount)?;
+    
+    let scale = 10u64.checked_pow(escrow.decimals as u32).ok_or(GovernanceError::MathOverflow)?;
+    let community = &mut ctx.accounts.community;
+    community.total_escrow_weight = community.total_escrow_weight.checked_sub(escrow.amount / scale)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    let (community_key, user_key) = (ctx.accounts.community.key(), ctx.accounts.authority.key());
+    checkpoint_escrow(&mut ctx.accounts.power_checkpoints, community_key, user_key, None, Clock::get()?.unix_timestamp);
+    fit_checkpoints(&ctx.accounts.power_checkpoints, ctx.accounts.authority.to_account_info(), ctx.accounts.system_program.to_account_info())?;
+    
+    Ok(())
+}
+
+pub fn promote_member(
+    ctx: Context<ManageMember>,
+) -> Result<()> {
+    if !ctx.accounts.admin_membership.role.has_permission(Permission::ManageMembers) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    let member = &mut ctx.accounts.member_membership;
+    member.role = member.role.promoted().ok_or(GovernanceError::InvalidRoleChange)?;
+    
+    Ok(())
+}
+
+pub fn demote_member(
+    ctx: Context<ManageMember>,
+) -> Result<()> {
+    if !ctx.accounts.admin_membership.role.has_permission(Permission::ManageMembers) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    // The community creator always keeps admin rights
+    let member = &mut ctx.accounts.member_membership;
+    if member.user == ctx.accounts.community.authority {
+        return Err(GovernanceError::CommunityAuthorityProtected.into());
+    }
+    
+    member.role = member.role.demoted().ok_or(GovernanceError::InvalidRoleChange)?;
+    
+    Ok(())
+}
+
+pub fn remove_member(
+    ctx: Context<ManageMember>,
+) -> Result<()> {
+    if !ctx.accounts.admin_membership.role.has_permission(Permission::ManageMembers) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    let community = &mut ctx.accounts.community;
+    let member = &mut ctx.accounts.member_membership;
+    if member.user == community.authority {
+        return Err(GovernanceError::CommunityAuthorityProtected.into());
+    }
+    
+    // Removed memberships stay on chain so removed users cannot simply rejoin
+    member.is_active = false;
+    member.removed = true;
+    member.role = MemberRole::Member;
+    
+    community.member_count = community.member_count.checked_sub(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    community.total_voting_power = community.total_voting_power.checked_sub(member.voting_power)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+pub fn leave_community(
+    ctx: Context<LeaveCommunity>,
+) -> Result<()> {
+    let community = &mut ctx.accounts.community;
+    if ctx.accounts.user.key() == community.authority {
+        return Err(GovernanceError::CommunityAuthorityProtected.into());
+    }
+    
+    if ctx.accounts.membership.deposited_amount > 0 || ctx.accounts.membership.conviction_staked > 0 {
+        return Err(GovernanceError::OutstandingDeposit.into());
+    }
+    
+    if ctx.accounts.membership.delegated_to.is_some() {
+        return Err(GovernanceError::FactionDelegationActive.into());
+    }
+    
+    // Membership account is closed to the user by the account constraint
+    community.member_count = community.member_count.checked_sub(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    community.total_voting_power = community.total_voting_power.checked_sub(ctx.accounts.membership.voting_power)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+pub fn create_proposal(
+    ctx: Context<CreateProposal>,
+    args: CreateProposalArgs,
+    epoch: u64,
+) -> Result<()> {
+    let CreateProposalArgs {
+        title,
+        description,
+        is_investment_proposal,
+        target_collection,
+        investment_amount,
+        action,
+        ballot_type,
+        options,
+    } = args;
+    
+    // Check if user is a member of the community
+    let membership = &ctx.accounts.membership;
+    if !membership.is_active {
+        return Err(GovernanceError::NotCommunityMember.into());
+    }
+    
+    if !membership.role.has_permission(Permission::CreateProposal) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    // Validate typed actions up front, accounts are checked on execution
+    match &action {
+        Some(ProposalAction::TreasuryTransfer(transfer)) if transfer.amount == 0 => {
+            return Err(GovernanceError::InvalidAmount.into());
+        },
+        Some(ProposalAction::InvestInVault { price, fraction_amount, .. }) if *price == 0 || *fraction_amount == 0 => {
+            return Err(GovernanceError::InvalidAmount.into());
+        },
+        Some(ProposalAction::FundStrategy { amount, .. }) | Some(ProposalAction::FundRewardPool { amount, .. }) if *amount == 0 => {
+            return Err(GovernanceError::InvalidAmount.into());
+        },
+        Some(ProposalAction::UpdateAllocations { allocation_percentages, .. })
+            if allocation_percentages.iter().map(|percentage| *percentage as u32).sum::<u32>() != 100 =>
+        {
+            return Err(GovernanceError::InvalidInvestmentAllocation.into());
+        },
+        Some(ProposalAction::UpdateConfig(update))
+            if matches!(update.voting_period, Some(period) if period <= 0)
+                || matches!(update.reputation_decay_bps, Some(decay_bps) if decay_bps > 10_000) =>
+        {
+            return Err(GovernanceError::InvalidGovernanceConfig.into());
+        },
+        Some(ProposalAction::AddStrategy { risk_level, target_collections, allocation_percentages, .. }) => {
+            validate_strategy(*risk_level, target_collections, allocation_percentages)?;
+        },
+        Some(ProposalAction::SetCouncilThreshold { approval_threshold }) if *approval_threshold as usize > MAX_CURATORS => {
+            return Err(GovernanceError::InvalidCouncilChange.into());
+        },
+        _ => {},
+    }
+    
+    // Binary proposals have no options, multi-option ballots need at least two
+    let options_valid = match ballot_type {
+        BallotType::Binary => options.is_empty(),
+        _ => options.len() >= 2
+            && options.len() <= MAX_PROPOSAL_OPTIONS
+            && options.iter().all(|label| !label.is_empty()),
+    };
+    if !options_valid {
+        return Err(GovernanceError::InvalidProposalOptions.into());
+    }
+    
+    // Actions run when the proposal passes, which only a binary vote expresses
+    if action.is_some() && ballot_type != BallotType::Binary {
+        return Err(GovernanceError::InvalidProposalAction.into());
+    }
+    
+    // Plans up for approval are frozen, so the vote is on the orders the proposal was made for
+    if let Some(ProposalAction::ApproveRebalance { plan, computed_at }) = &action {
+        let community_key = ctx.accounts.community.key();
+        let Some(rebalance_plan) = ctx.accounts.rebalance_plan.as_mut() else {
+            return Err(GovernanceError::InvalidProposalAction.into());
+        };
+        if rebalance_plan.key() != *plan
+            || rebalance_plan.community != community_key
+            || rebalance_plan.computed_at != *computed_at
+            || rebalance_plan.approved
+        {
+            return Err(GovernanceError::InvalidProposalAction.into());
+        }
+        rebalance_plan.frozen = true;
+    }
+    
+    let community = &mut ctx.accounts.community;
+    let proposal = &mut ctx.accounts.proposal;
+    let governance = &ctx.accounts.governance;
+    
+    // Set up proposal details
+    proposal.governance = governance.key();
+    proposal.community = community.key();
+    proposal.proposer = ctx.accounts.authority.key();
+    proposal.title = title;
+    proposal.description = description;
+    proposal.status = ProposalStatus::Active;
+    
+    let current_time = Clock::get()?.unix_timestamp;
+    if epoch != governance.epoch_at(current_time) {
+        return Err(GovernanceError::InvalidEpoch.into());
+    }
+    
+    proposal.start_time = current_time;
+    proposal.end_time = current_time.checked_add(governance.voting_period)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    proposal.instruction_count = 0;
+    proposal.for_votes = 0;
+    proposal.against_votes = 0;
+    proposal.abstain_votes = 0;
+    proposal.executed_at = None;
+    proposal.is_investment_proposal = is_investment_proposal
+        || matches!(
+            action,
+            Some(ProposalAction::InvestInVault { .. })
+                | Some(ProposalAction::FundStrategy { .. })
+                | Some(ProposalAction::ApproveFederated { .. })
+        );
+    proposal.target_collection = target_collection;
+    proposal.investment_amount = investment_amount;
+    proposal.canceled_by = None;
+    proposal.canceled_at = None;
+    proposal.succeeded_at = None;
+    proposal.action = action;
+    proposal.voting_mode = community.voting_mode;
+    proposal.ballot_type = ballot_type;
+    proposal.options = options.into_iter()
+        .map(|label| ProposalOption { label, vote_weight: 0, instruction_count: 0 })
+        .collect();
+    proposal.ballot_weight = 0;
+    proposal.winning_option = None;
+    proposal.is_secret_ballot = community.reveal_period > 0;
+    proposal.reveal_end_time = proposal.end_time.checked_add(community.reveal_period)
+        .ok_or(GovernanceError::MathOverflow)?;
+    proposal.deposit_amount = community.proposal_deposit;
+    proposal.deposit_mint = community.deposit_mint;
+    proposal.quorum_reached = false;
+    
+    // Investment proposals also need the curator council's approval, fund-moving actions whatever the proposer flagged
+    let requires_council = proposal.is_investment_proposal
+        || proposal.action.as_ref().is_some_and(ProposalAction::requires_council);
+    proposal.council_threshold = if requires_council { community.council_threshold } else { 0 };
+    proposal.council_approvers = Vec::new();
+    proposal.reputation_voting = governance.reputation_voting;
+    proposal.signed_vote_count = 0;
+    proposal.vote_count = 0;
+    proposal.snapshot_voting_power = community.total_voting_power;
+    proposal.snapshot_escrow_weight = community.total_escrow_weight;
+    
+    // Lock the proposal deposit, SOL on the proposal account or tokens in a deposit vault
+    if proposal.deposit_amount > 0 {
+        match proposal.deposit_mint {
+            None => {
+                let transfer_ix = anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: proposal.to_account_info(),
+                };
+                
+                let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix);
+                anchor_lang::system_program::transfer(cpi_ctx, proposal.deposit_amount)?;
+            },
+            Some(mint) => {
+                let (Some(deposit_mint), Some(deposit_vault), Some(proposer_deposit_account), Some(token_program)) = (
+                    ctx.accounts.deposit_mint.as_ref(),
+                    ctx.accounts.deposit_vault.as_ref(),
+                    ctx.accounts.proposer_deposit_account.as_ref(),
+                    ctx.accounts.token_program.as_ref(),
+                ) else {
+                    return Err(GovernanceError::InvalidDepositAccount.into());
+                };
+                
+                if deposit_mint.key() != mint {
+                    return Err(GovernanceError::InvalidDepositAccount.into());
+                }
+                
+                let transfer_ix = anchor_spl::token::Transfer {
+                    from: proposer_deposit_account.to_account_info(),
+                    to: deposit_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                };
+                
+                let cpi_ctx = CpiContext::new(token_program.to_account_info(), transfer_ix);
+                anchor_spl::token::transfer(cpi_ctx, proposal.deposit_amount)?;
+            },
+        }
+    }
+    
+    // Increment proposal count
+    community.proposal_count = community.proposal_count.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    // Update reputation score for proposal creation
+    award_participation(&mut ctx.accounts.reputation, &mut ctx.accounts.community_reputation, governance, 25, current_time)?;
+    
+    // Accrue activity reward for the current epoch
+    let reward = &mut ctx.accounts.activity_reward;
+    reward.user = ctx.accounts.authority.key();
+    reward.governance = governance.key();
+    reward.epoch = epoch;
+    reward.amount = reward.amount.checked_add(50) // Reward amount for creating a proposal
+        .ok_or(GovernanceError::MathOverflow)?;
+    reward.timestamp = current_time;
+    
+    Ok(())
+}
+
+pub fn cast_vote(
+    ctx: Context<CastVote>,
+    vote_type: VoteType,
+    epoch: u64,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let membership = &mut ctx.accounts.membership;
+    
+    // Check if proposal is active
+    if proposal.status != ProposalStatus::Active {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    // Multi-option proposals are voted on with cast_ballot
+    if proposal.ballot_type != BallotType::Binary {
+        return Err(GovernanceError::InvalidBallot.into());
+    }
+    
+    if proposal.is_secret_ballot {
+        return Err(GovernanceError::SecretBallotRequired.into());
+    }
+    
+    // Check if voting period is active
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time > proposal.end_time {
+        return Err(GovernanceError::VotingEnded.into());
+    }
+    
+    if current_time < proposal.start_time {
+        return Err(GovernanceError::VotingNotStarted.into());
+    }
+    
+    if epoch != ctx.accounts.governance.epoch_at(current_time) {
+        return Err(GovernanceError::InvalidEpoch.into());
+    }
+    
+    // Check if user is a member of the community
+    if !membership.is_active || membership.community != proposal.community {
+        return Err(GovernanceError::NotCommunityMember.into());
+    }
+    
+    // Determine vote weight for the proposal's voting mode, scaled by decayed reputation when enabled
+    let vote_weight = calculate_vote_weight(proposal, &ctx.accounts.power_checkpoints)?;
+    decay_reputation(&mut ctx.accounts.reputation, &ctx.accounts.governance, current_time)?;
+    let vote_weight = reputation_weighted(proposal, vote_weight, &ctx.accounts.reputation)?;
+    
+    // Deposits back this vote until voting on the proposal ends
+    if membership.deposit_locked_until < proposal.reveal_end_time {
+        membership.deposit_locked_until = proposal.reveal_end_time;
+    }
+    
+    // Record vote
+    let vote = &mut ctx.accounts.vote;
+    vote.proposal = proposal.key();
+    vote.voter = ctx.accounts.authority.key();
+    vote.vote_type = vote_type;
+    vote.voting_power = vote_weight;
+    vote.vote_time = current_time;
+    vote.choices = Vec::new();
+    vote.commitment = None;
+    vote.is_revealed = true;
+    
+    // Update proposal vote counts
+    tally_vote(proposal, vote_type, vote_weight)?;
+    proposal.vote_count = proposal.vote_count.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    // Update reputation score for voting
+    award_participation(&mut ctx.accounts.reputation, &mut ctx.accounts.community_reputation, &ctx.accounts.governance, 10, current_time)?;
+    
+    // Accrue activity reward for the current epoch
+    let reward = &mut ctx.accounts.activity_reward;
+    reward.user = ctx.accounts.authority.key();
+    reward.governance = ctx.accounts.governance.key();
+    reward.epoch = epoch;
+    let reward_amount = boosted_reward(10, ctx.accounts.vote_escrow.as_deref(), current_time)?; // Reward amount for voting
+    reward.amount = reward.amount.checked_add(reward_amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    reward.timestamp = current_time;
+    
+    Ok(())
+}
+
+pub fn cast_ballot(
+    ctx: Context<CastBallot>,
+    choices: Vec<u8>,
+    epoch: u64,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let membership = &mut ctx.accounts.membership;
+    
+    // Check if proposal is active
+    if proposal.status != ProposalStatus::Active {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    if proposal.is_secret_ballot {
+        return Err(GovernanceError::SecretBallotRequired.into());
+    }
+    
+    // Check if voting period is active
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time > proposal.end_time {
+        return Err(GovernanceError::VotingEnded.into());
+    }
+    
+    if current_time < proposal.start_time {
+        return Err(GovernanceError::VotingNotStarted.into());
+    }
+    
+    if epoch != ctx.accounts.governance.epoch_at(current_time) {
+        return Err(GovernanceError::InvalidEpoch.into());
+    }
+    
+    let vote_weight = calculate_vote_weight(proposal, &ctx.accounts.power_checkpoints)?;
+    decay_reputation(&mut ctx.accounts.reputation, &ctx.accounts.governance, current_time)?;
+    let vote_weight = reputation_weighted(proposal, vote_weight, &ctx.accounts.reputation)?;
+    
+    // Deposits back this vote until voting on the proposal ends
+    if membership.deposit_locked_until < proposal.reveal_end_time {
+        membership.deposit_locked_until = proposal.reveal_end_time;
+    }
+    
+    tally_ballot(proposal, &choices, vote_weight)?;
+    proposal.vote_count = proposal.vote_count.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    // Record ballot
+    let vote = &mut ctx.accounts.vote;
+    vote.proposal = proposal.key();
+    vote.voter = ctx.accounts.authority.key();
+    vote.vote_type = VoteType::For; // Multi-option picks are recorded in choices
+    vote.voting_power = vote_weight;
+    vote.vote_time = current_time;
+    vote.choices = choices;
+    vote.commitment = None;
+    vote.is_revealed = true;
+    
+    // Update reputation score for voting
+    award_participation(&mut ctx.accounts.reputation, &mut ctx.accounts.community_reputation, &ctx.accounts.governance, 10, current_time)?;
+    
+    // Accrue activity reward for the current epoch
+    let reward = &mut ctx.accounts.activity_reward;
+    reward.user = ctx.accounts.authority.key();
+    reward.governance = ctx.accounts.governance.key();
+    reward.epoch = epoch;
+    let reward_amount = boosted_reward(10, ctx.accounts.vote_escrow.as_deref(), current_time)?; // Reward amount for voting
+    reward.amount = reward.amount.checked_add(reward_amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    reward.timestamp = current_time;
+    
+    Ok(())
+}
+
+pub fn commit_vote(
+    ctx: Context<CommitVote>,
+    commitment: [u8; 32],
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let membership = &mut ctx.accounts.membership;
+    
+    if !proposal.is_secret_ballot {
+        return Err(GovernanceError::NotSecretBallot.into());
+    }
+    
+    // Commitments are accepted during the regular voting period
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time > proposal.end_time {
+        return Err(GovernanceError::VotingEnded.into());
+    }
+    
+    if current_time < proposal.start_time {
+        return Err(GovernanceError::VotingNotStarted.into());
+    }
+    
+    // Weight is fixed at commit time, scaled by decayed reputation when enabled,
+    // and deposits stay locked through the reveal phase
+    let vote_weight = calculate_vote_weight(proposal, &ctx.accounts.power_checkpoints)?;
+    decay_reputation(&mut ctx.accounts.reputation, &ctx.accounts.governance, current_time)?;
+    let vote_weight = reputation_weighted(proposal, vote_weight, &ctx.accounts.reputation)?;
+    if membership.deposit_locked_until < proposal.reveal_end_time {
+        membership.deposit_locked_until = proposal.reveal_end_time;
+    }
+    
+    let vote = &mut ctx.accounts.vote;
+    vote.proposal = proposal.key();
+    vote.voter = ctx.accounts.authority.key();
+    vote.vote_type = VoteType::Abstain; // Replaced on reveal
+    vote.voting_power = vote_weight;
+    vote.vote_time = current_time;
+    vote.choices = Vec::new();
+    vote.commitment = Some(commitment);
+    vote.is_revealed = false;
+    
+    proposal.vote_count = proposal.vote_count.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+pub fn reveal_vote(
+    ctx: Context<RevealVote>,
+    vote_type: VoteType,
+    choices: Vec<u8>,
+    salt: [u8; 32],
+    epoch: u64,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let vote = &mut ctx.accounts.vote;
+    
+    if vote.is_revealed {
+        return Err(GovernanceError::AlreadyRevealed.into());
+    }
+    
+    // Reveals are accepted between the end of voting and the end of the reveal phase
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time <= proposal.end_time || current_time > proposal.reveal_end_time {
+        return Err(GovernanceError::RevealNotOpen.into());
+    }
+    
+    if epoch != ctx.accounts.governance.epoch_at(current_time) {
+        return Err(GovernanceError::InvalidEpoch.into());
+    }
+    
+    let expected = ballot_commitment(&vote.voter, vote_type, &choices, &salt);
+    if vote.commitment != Some(expected) {
+        return Err(GovernanceError::CommitmentMismatch.into());
+    }
+    
+    // Tally the revealed vote with the weight recorded at commit time
+    if proposal.ballot_type == BallotType::Binary {
+        tally_vote(proposal, vote_type, vote.voting_power)?;
+    } else {
+        tally_ballot(proposal, &choices, vote.voting_power)?;
+    }
+    
+    vote.vote_type = vote_type;
+    vote.choices = choices;
+    vote.is_revealed = true;
+    
+    // Update reputation score for voting
+    award_participation(&mut ctx.accounts.reputation, &mut ctx.accounts.community_reputation, &ctx.accounts.governance, 10, current_time)?;
+    
+    // Accrue activity reward for the current epoch
+    let reward = &mut ctx.accounts.activity_reward;
+    reward.user = ctx.accounts.authority.key();
+    reward.governance = ctx.accounts.governance.key();
+    reward.epoch = epoch;
+    let reward_amount = boosted_reward(10, ctx.accounts.vote_escrow.as_deref(), current_time)?; // Reward amount for voting
+    reward.amount = reward.amount.checked_add(reward_amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    reward.timestamp = current_time;
+    
+    Ok(())
+}
+
+pub fn insert_proposal_instructions(
+    ctx: Context<InsertProposalInstructions>,
+    option_index: u8,
+    instructions: Vec<ProposalInstruction>,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    
+    // Instructions are fixed before anyone votes, secret commitments included
+    if proposal.status != ProposalStatus::Active || proposal.vote_count > 0 {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    if Clock::get()?.unix_timestamp > proposal.end_time {
+        return Err(GovernanceError::VotingEnded.into());
+    }
+    
+    if instructions.is_empty() {
+        return Err(GovernanceError::InvalidProposalInstructions.into());
+    }
+    validate_proposal_instructions(&instructions, &proposal.community, ctx.program_id)?;
+    
+    let added = u8::try_from(instructions.len()).map_err(|_| GovernanceError::MathOverflow)?;
+    if proposal.ballot_type == BallotType::Binary {
+        if option_index != 0 {
+            return Err(GovernanceError::InvalidProposalOptions.into());
+        }
+    } else {
+        let option = proposal.options.get_mut(option_index as usize)
+            .ok_or(GovernanceError::InvalidProposalOptions)?;
+        option.instruction_count = option.instruction_count.checked_add(added)
+            .ok_or(GovernanceError::MathOverflow)?;
+    }
+    
+    proposal.instruction_count = proposal.instruction_count.checked_add(added)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    let proposal_instructions = &mut ctx.accounts.proposal_instructions;
+    proposal_instructions.proposal = proposal.key();
+    proposal_instructions.option_index = option_index;
+    proposal_instructions.instructions = instructions;
+    
+    Ok(())
+}
+
+pub fn finalize_proposal(
+    ctx: Context<FinalizeProposal>,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let governance = &ctx.accounts.governance;
+    
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // Check if proposal is still active
+    if proposal.status != ProposalStatus::Active {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    // Calculate total votes
+    let total_votes = if proposal.ballot_type == BallotType::Binary {
+        proposal.for_votes
+            .checked_add(proposal.against_votes).ok_or(GovernanceError::MathOverflow)?
+            .checked_add(proposal.abstain_votes).ok_or(GovernanceError::MathOverflow)?
+    } else {
+        proposal.ballot_weight
+    };
+    
+    // Quorum decides whether the proposal deposit is refunded
+    proposal.quorum_reached = total_votes >= governance.min_vote_threshold;
+    
+    // Before the voting period, including any reveal phase, has ended only a decided outcome can finalize
+    if current_time <= proposal.reveal_end_time {
+        match tipped_outcome(proposal, &ctx.accounts.community, governance.min_vote_threshold)? {
+            Some(true) => {
+                proposal.status = ProposalStatus::Succeeded;
+                proposal.succeeded_at = Some(current_time);
+            },
+            Some(false) => proposal.status = ProposalStatus::Defeated,
+            None => return Err(GovernanceError::VotingNotStarted.into()),
+        }
+    } else if !proposal.quorum_reached {
+        // Missing quorum defeats the proposal and forfeits its deposit
+        proposal.status = ProposalStatus::Defeated;
+    } else {
+        // Determine outcome
+        if proposal.ballot_type == BallotType::Binary {
+            if proposal.for_votes > proposal.against_votes {
+                proposal.status = ProposalStatus::Succeeded;
+                proposal.succeeded_at = Some(current_time);
+            } else {
+                proposal.status = ProposalStatus::Defeated;
+            }
+        } else {
+            // The option with the highest tally wins, a tie for first place defeats the proposal
+            let mut winner: Option<usize> = None;
+            let mut tied = false;
+            for (index, option) in proposal.options.iter().enumerate() {
+                match winner {
+                    Some(best) if option.vote_weight < proposal.options[best].vote_weight => {},
+                    Some(best) if option.vote_weight == proposal.options[best].vote_weight => tied = true,
+                    _ => {
+                        winner = Some(index);
+                        tied = false;
+                    },
+                }
+            }
+            
+            match winner {
+                Some(index) if !tied && proposal.options[index].vote_weight > 0 => {
+                    proposal.winning_option = Some(index as u8);
+                    proposal.status = ProposalStatus::Succeeded;
+                    proposal.succeeded_at = Some(current_time);
+                },
+                _ => proposal.status = ProposalStatus::Defeated,
+            }
+        }
+    }
+    
+    // A passing community vote still needs the council's approval, counting only current curators
+    if proposal.status == ProposalStatus::Succeeded && proposal.council_threshold > 0 {
+        let council = ctx.accounts.council.as_ref()
+            .ok_or(GovernanceError::InvalidCouncilAccount)?;
+        let approvals = proposal.council_approvers.iter()
+            .filter(|curator| council.curators.contains(curator))
+            .count();
+        
+        if approvals < proposal.council_threshold as usize {
+            if current_time <= proposal.reveal_end_time {
+                return Err(GovernanceError::CouncilApprovalPending.into());
+            }
+            
+            proposal.status = ProposalStatus::Defeated;
+            proposal.succeeded_at = None;
+            proposal.winning_option = None;
+        }
+    }
+    
+    // Update proposer's reputation if proposal succeeded, penalize it on defeat
+    let reputation = &mut ctx.accounts.proposer_reputation;
+    let breakdown = &mut ctx.accounts.proposer_community_reputation;
+    if proposal.status == ProposalStatus::Succeeded {
+        award_participation(reputation, breakdown, governance, 50, current_time)?;
+            
+        // Extra points for investment proposals
+        if proposal.is_investment_proposal {
+            credit_reputation(reputation, breakdown, 25, 0, current_time)?;
+        }
+    } else {
+        penalize_reputation(reputation, breakdown, governance, governance.defeat_penalty, current_time)?;
+    }
+    
+    Ok(())
+}
+
+// Transfers tokens out of a community treasury, signed by the treasury PDA
+fn pay_from_treasury<'info>(
+    treasury: &mut Account<'info, CommunityTreasury>,
+    treasury_token_account: &Account<'info, anchor_spl::token::TokenAccount>,
+    destination_token_account: &Account<'info, anchor_spl::token::TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let transfer_ix = anchor_spl::token::Transfer {
+        from: treasury_token_account.to_account_info(),
+        to: destination_token_account.to_account_info(),
+        authority: treasury.to_account_info(),
+    };
+    
+    let treasury_seeds = &[
+        b"treasury".as_ref(),
+        treasury.community.as_ref(),
+        treasury.mint.as_ref(),
+        &[treasury.bump],
+    ];
+    
+    let signer_seeds = &[&treasury_seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        transfer_ix,
+        signer_seeds,
+    );
+    
+    anchor_spl::token::transfer(cpi_ctx, amount)?;
+    
+    treasury.total_spent = treasury.total_spent.checked_add(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+// Stored instructions run signed by the community PDA, so they may not call back into this program
+// or name the vaults holding members' tokens
+fn validate_proposal_instructions(instructions: &[ProposalInstruction], community: &Pubkey, program_id: &Pubkey) -> Result<()> {
+    let (voting_vault, _) = Pubkey::find_program_address(&[b"voting_vault".as_ref(), community.as_ref()], program_id);
+    let (vault_authority, _) = Pubkey::find_program_address(&[b"vault_authority".as_ref(), community.as_ref()], program_id);
+    
+    for instruction in instructions {
+        if instruction.program_id == *program_id
+            || instruction.accounts.iter().any(|account| account.pubkey == voting_vault || account.pubkey == vault_authority)
+        {
+            return Err(GovernanceError::InvalidProposalInstructions.into());
+        }
+    }
+    Ok(())
+}
+
+pub fn execute_proposal<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let governance = &ctx.accounts.governance;
+    
+    // Check if proposal succeeded
+    if proposal.status != ProposalStatus::Succeeded {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    // Moderators keep their veto until the veto window closes, even after an early finalization
+    let current_time = Clock::get()?.unix_timestamp;
+    let veto_deadline = proposal.reveal_end_time.checked_add(ctx.accounts.community.veto_window)
+        .ok_or(GovernanceError::MathOverflow)?;
+    if current_time <= veto_deadline {
+        return Err(GovernanceError::VetoWindowOpen.into());
+    }
+    
+    // Enforce the timelock and the execution window, which opens once both the timelock and the veto window passed
+    let succeeded_at = proposal.succeeded_at.ok_or(GovernanceError::InvalidProposalState)?;
+    let executable_from = succeeded_at.checked_add(governance.execution_delay)
+        .ok_or(GovernanceError::MathOverflow)?;
+    if current_time < executable_from {
+        return Err(GovernanceError::TimelockNotElapsed.into());
+    }
+    let executable_from = executable_from.max(veto_deadline.saturating_add(1));
+    
+    if governance.execution_window > 0 {
+        let executable_until = executable_from.checked_add(governance.execution_window)
+            .ok_or(GovernanceError::MathOverflow)?;
+        if current_time > executable_until {
+            return Err(GovernanceError::ExecutionWindowExpired.into());
+        }
+    }
+    
+    // Marked executed before any stored instruction runs, so none can execute it again
+    proposal.status = ProposalStatus::Executed;
+    proposal.executed_at = Some(current_time);
+    proposal.exit(ctx.program_id)?;
+    
+    // Run the stored instructions of the winning option, signed by the community PDA
+    let option_index = proposal.winning_option.unwrap_or(0);
+    let expected_instructions = if proposal.ballot_type == BallotType::Binary {
+        proposal.instruction_count
+    } else {
+        proposal.options[option_index as usize].instruction_count
+    };
+    
+    if let Some(proposal_instructions) = ctx.accounts.proposal_instructions.as_ref() {
+        if proposal_instructions.proposal != proposal.key() || proposal_instructions.option_index != option_index {
+            return Err(GovernanceError::InvalidProposalInstructions.into());
+        }
+        validate_proposal_instructions(&proposal_instructions.instructions, &proposal.community, ctx.program_id)?;
+        
+        let community = &ctx.accounts.community;
+        let community_seeds = &[
+            b"community".as_ref(),
+            community.governance.as_ref(),
+            community.name.as_bytes(),
+            &[ctx.bumps.community],
+        ];
+        
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(community.to_account_info());
+        
+        for stored in proposal_instructions.instructions.iter() {
+            let instruction = Instruction {
+                program_id: stored.program_id,
+                accounts: stored.accounts.iter()
+                    .map(|account| AccountMeta {
+                        pubkey: account.pubkey,
+                        is_signer: account.is_signer,
+                        is_writable: account.is_writable,
+                    })
+                    .collect(),
+                data: stored.data.clone(),
+            };
+            
+            invoke_signed(&instruction, &account_infos, &[&community_seeds[..]])?;
+        }
+    } else if expected_instructions > 0 {
+        return Err(GovernanceError::InvalidProposalInstructions.into());
+    }
+    
+    // Stored instructions may have amended accounts that are written back on exit
+    ctx.accounts.governance.reload()?;
+    ctx.accounts.community.reload()?;
+    
+    // Carry out the typed action natively
+    match proposal.action.clone() {
+        Some(ProposalAction::TreasuryTransfer(transfer)) => {
+            let (Some(treasury), Some(treasury_token_account), Some(destination_token_account), Some(token_program)) = (
+                ctx.accounts.treasury.as_mut(),
+                ctx.accounts.treasury_token_account.as_ref(),
+                ctx.accounts.destination_token_account.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+            ) else {
+                return Err(GovernanceError::InvalidTreasuryAccount.into());
+            };
+            
+            if treasury.key() != transfer.treasury
+                || treasury.community != proposal.community
+                || treasury_token_account.key() != treasury.token_account
+                || destination_token_account.key() != transfer.destination
+            {
+                return Err(GovernanceError::InvalidTreasuryAccount.into());
+            }
+            
+            pay_from_treasury(treasury, treasury_token_account, destination_token_account, token_program, transfer.amount)?;
+        },
+        Some(ProposalAction::InvestInVault { vault, treasury: payment_treasury, fraction_treasury, seller, payment_destination, price, fraction_amount }) => {
+            let (
+                Some(treasury),
+                Some(treasury_token_account),
+                Some(destination_token_account),
+                Some(token_program),
+                Some(fractional_vault),
+                Some(fraction_treasury_account),
+                Some(fraction_treasury_token_account),
+                Some(seller_fraction_account),
+            ) = (
+                ctx.accounts.treasury.as_mut(),
+                ctx.accounts.treasury_token_account.as_ref(),
+                ctx.accounts.destination_token_account.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+                ctx.accounts.vault.as_ref(),
+                ctx.accounts.fraction_treasury.as_mut(),
+                ctx.accounts.fraction_treasury_token_account.as_ref(),
+                ctx.accounts.seller_fraction_account.as_ref(),
+            ) else {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            };
+            
+            if treasury.key() != payment_treasury
+                || treasury.community != proposal.community
+                || treasury_token_account.key() != treasury.token_account
+                || destination_token_account.key() != payment_destination
+            {
+                return Err(GovernanceError::InvalidTreasuryAccount.into());
+            }
+            
+            // The seller settles the trade by executing the proposal with their fraction tokens
+            if fractional_vault.key() != vault
+                || !fractional_vault.is_active
+                || ctx.accounts.authority.key() != seller
+                || fraction_treasury_account.key() != fraction_treasury
+                || fraction_treasury_account.community != proposal.community
+                || fraction_treasury_account.mint != fractional_vault.token_mint
+                || fraction_treasury_token_account.key() != fraction_treasury_account.token_account
+                || seller_fraction_account.mint != fractional_vault.token_mint
+                || seller_fraction_account.owner != seller
+            {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            }
+            
+            let fraction_ix = anchor_spl::token::Transfer {
+                from: seller_fraction_account.to_account_info(),
+                to: fraction_treasury_token_account.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            };
+            
+            let cpi_ctx = CpiContext::new(token_program.to_account_info(), fraction_ix);
+            anchor_spl::token::transfer(cpi_ctx, fraction_amount)?;
+            
+            fraction_treasury_account.total_contributed = fraction_treasury_account.total_contributed.checked_add(fraction_amount)
+                .ok_or(GovernanceError::MathOverflow)?;
+            
+            pay_from_treasury(treasury, treasury_token_account, destination_token_account, token_program, price)?;
+        },
+        Some(ProposalAction::UpdateConfig(update)) => {
+            let (Some(amendment), Some(approval)) = (
+                ctx.accounts.config_amendment.as_mut(),
+                ctx.accounts.amendment_approval.as_mut(),
+            ) else {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            };
+            
+            // The shared config only changes once a majority of recognized communities approved the same update
+            if !ctx.accounts.community.recognized {
+                return Err(GovernanceError::CommunityNotRecognized.into());
+            }
+            if amendment.approvals == 0 {
+                amendment.governance = proposal.governance;
+                amendment.config_version = ctx.accounts.governance.config_version;
+                amendment.update = update;
+                amendment.created_at = current_time;
+            }
+            amendment.approvals = amendment.approvals.checked_add(1)
+                .ok_or(GovernanceError::MathOverflow)?;
+            
+            approval.amendment = amendment.key();
+            approval.community = proposal.community;
+        },
+        Some(ProposalAction::VerifyCollection { rating_system, collection }) => {
+            let (Some(rating_system_account), Some(collection_account)) = (
+                ctx.accounts.rating_system.as_ref(),
+                ctx.accounts.collection.as_mut(),
+            ) else {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            };
+            
+            // Only rating systems administered by the community PDA can be driven by its proposals
+            if rating_system_account.key() != rating_system
+                || !rating_system_account.is_active
+                || rating_system_account.authority != proposal.community
+                || collection_account.key() != collection
+                || collection_account.rating_system != rating_system
+            {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            }
+            
+            collection_account.is_verified = true;
+        },
+        Some(ProposalAction::AddStrategy { name, description, risk_level, target_collections, allocation_percentages }) => {
+            let Some(strategy) = ctx.accounts.strategy.as_mut() else {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            };
+            
+            strategy.community = proposal.community;
+            strategy.name = name;
+            strategy.description = description;
+            strategy.creator = proposal.proposer;
+            strategy.risk_level = risk_level;
+            strategy.target_collections = target_collections;
+            strategy.allocation_percentages = allocation_percentages;
+            strategy.creation_time = current_time;
+            strategy.last_updated = current_time;
+            strategy.performance_score = 0;
+            strategy.is_active = true;
+            
+            credit_reputation(&mut ctx.accounts.proposer_reputation, &mut ctx.accounts.proposer_community_reputation, 30, 0, current_time)?;
+        },
+        Some(ProposalAction::RemoveMember { membership }) => {
+            let Some(member) = ctx.accounts.member_membership.as_mut() else {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            };
+            
+            if member.key() != membership || member.community != proposal.community || !member.is_active {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            }
+            
+            let community = &mut ctx.accounts.community;
+            if member.user == community.authority {
+                return Err(GovernanceError::CommunityAuthorityProtected.into());
+            }
+            
+            member.is_active = false;
+            member.removed = true;
+            member.role = MemberRole::Member;
+            
+            community.member_count = community.member_count.checked_sub(1)
+                .ok_or(GovernanceError::MathOverflow)?;
+            community.total_voting_power = community.total_voting_power.checked_sub(member.voting_power)
+                .ok_or(GovernanceError::MathOverflow)?;
+        },
+        Some(ProposalAction::FundStrategy { strategy, treasury: funding_treasury, amount }) => {
+            let (Some(treasury), Some(treasury_token_account), Some(destination_token_account), Some(token_program), Some(portfolio)) = (
+                ctx.accounts.treasury.as_mut(),
+                ctx.accounts.treasury_token_account.as_ref(),
+                ctx.accounts.destination_token_account.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+                ctx.accounts.portfolio.as_mut(),
+            ) else {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            };
+            
+            // Funds go from the community treasury into the portfolio's cash account
+            if treasury.key() != funding_treasury
+                || treasury.community != proposal.community
+                || treasury.mint != portfolio.base_mint
+                || treasury_token_account.key() != treasury.token_account
+                || portfolio.strategy != strategy
+                || portfolio.community != proposal.community
+                || destination_token_account.key() != portfolio.cash_account
+            {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            }
+            
+            pay_from_treasury(treasury, treasury_token_account, destination_token_account, token_program, amount)?;
+            
+            portfolio.total_funded = portfolio.total_funded.checked_add(amount)
+                .ok_or(GovernanceError::MathOverflow)?;
+            portfolio.cash = portfolio.cash.checked_add(amount)
+                .ok_or(GovernanceError::MathOverflow)?;
+        },
+        Some(ProposalAction::UpdateAllocations { strategy, allocation_percentages }) => {
+            let Some(target_strategy) = ctx.accounts.target_strategy.as_mut() else {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            };
+            
+            if target_strategy.key() != strategy || target_strategy.community != proposal.community {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            }
+            
+            validate_strategy(target_strategy.risk_level, &target_strategy.target_collections, &allocation_percentages)?;
+            
+            target_strategy.allocation_percentages = allocation_percentages;
+            target_strategy.last_updated = current_time;
+        },
+        Some(ProposalAction::ApproveRebalance { plan, computed_at }) => {
+            let Some(rebalance_plan) = ctx.accounts.rebalance_plan.as_mut() else {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            };
+            
+            // The plan must not have been recomputed since the proposal was made
+            if rebalance_plan.key() != plan
+                || rebalance_plan.community != proposal.community
+                || rebalance_plan.computed_at != computed_at
+                || rebalance_plan.approved
+            {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            }
+            
+            rebalance_plan.approved = true;
+        },
+        Some(ProposalAction::ApproveFederated { federated_proposal }) => {
+            let Some(federation) = ctx.accounts.federated_proposal.as_mut() else {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            };
+            
+            if federation.key() != federated_proposal
+                || federation.governance != proposal.governance
+                || federation.status != FederatedStatus::Voting
+                || current_time > federation.deadline
+            {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            }
+            
+            let participant = federation.participants.iter_mut()
+                .find(|participant| participant.community == proposal.community)
+                .ok_or(GovernanceError::InvalidProposalAction)?;
+            if participant.decision != FederatedDecision::Pending {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            }
+            participant.decision = FederatedDecision::Approved;
+        },
+        Some(ProposalAction::FundRewardPool { treasury: funding_treasury, amount }) => {
+            let (Some(treasury), Some(treasury_token_account), Some(destination_token_account), Some(token_program), Some(reward_pool)) = (
+                ctx.accounts.treasury.as_mut(),
+                ctx.accounts.treasury_token_account.as_ref(),
+                ctx.accounts.destination_token_account.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+                ctx.accounts.reward_pool.as_mut(),
+            ) else {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            };
+            
+            // Reward tokens go from the community treasury into the pool vault
+            if treasury.key() != funding_treasury
+                || treasury.community != proposal.community
+                || treasury.mint != reward_pool.reward_mint
+                || treasury_token_account.key() != treasury.token_account
+                || reward_pool.governance != proposal.governance
+                || destination_token_account.key() != reward_pool.vault
+            {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            }
+            
+            pay_from_treasury(treasury, treasury_token_account, destination_token_account, token_program, amount)?;
+            
+            reward_pool.total_funded = reward_pool.total_funded.checked_add(amount)
+                .ok_or(GovernanceError::MathOverflow)?;
+        },
+        Some(ProposalAction::AppointCurator { curator }) => {
+            let Some(council) = ctx.accounts.council.as_mut() else {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            };
+            
+            if council.curators.contains(&curator) || council.curators.len() >= MAX_CURATORS {
+                return Err(GovernanceError::InvalidCouncilChange.into());
+            }
+            
+            council.curators.push(curator);
+        },
+        Some(ProposalAction::RemoveCurator { curator }) => {
+            let Some(council) = ctx.accounts.council.as_mut() else {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            };
+            
+            // The remaining curators must still be able to reach the threshold
+            let Some(position) = council.curators.iter().position(|existing| *existing == curator) else {
+                return Err(GovernanceError::InvalidCouncilChange.into());
+            };
+            if council.curators.len() - 1 < ctx.accounts.community.council_threshold as usize {
+                return Err(GovernanceError::InvalidCouncilChange.into());
+            }
+            
+            council.curators.remove(position);
+        },
+        Some(ProposalAction::SetCouncilThreshold { approval_threshold }) => {
+            let Some(council) = ctx.accounts.council.as_ref() else {
+                return Err(GovernanceError::InvalidProposalAction.into());
+            };
+            
+            if approval_threshold as usize > council.curators.len() {
+                return Err(GovernanceError::InvalidCouncilChange.into());
+            }
+            
+            ctx.accounts.community.council_threshold = approval_threshold;
+        },
+        None => {},
+    }
+    
+    // Update proposer's reputation for successful execution
+    award_participation(&mut ctx.accounts.proposer_reputation, &mut ctx.accounts.proposer_community_reputation, &ctx.accounts.governance, 25, current_time)?;
+    
+    Ok(())
+}
+
+pub fn create_treasury(
+    ctx: Context<CreateTreasury>,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::ManageTreasury) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.community = ctx.accounts.community.key();
+    treasury.mint = ctx.accounts.mint.key();
+    treasury.token_account = ctx.accounts.treasury_token_account.key();
+    treasury.total_contributed = 0;
+    treasury.total_spent = 0;
+    treasury.bump = ctx.bumps.treasury;
+    
+    Ok(())
+}
+
+pub fn contribute_to_treasury(
+    ctx: Context<ContributeToTreasury>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Err(GovernanceError::InvalidAmount.into());
+    }
+    
+    // Transfer tokens from contributor to the treasury
+    let transfer_ix = anchor_spl::token::Transfer {
+        from: ctx.accounts.contributor_token_account.to_account_info(),
+        to: ctx.accounts.treasury_token_account.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+    };
+    
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+    );
+    
+    anchor_spl::token::transfer(cpi_ctx, amount)?;
+    
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.total_contributed = treasury.total_contributed.checked_add(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    // One contribution point per whole token contributed
+    let points = amount
+        .checked_div(10u64.checked_pow(ctx.accounts.mint.decimals as u32).ok_or(GovernanceError::MathOverflow)?)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    credit_reputation(&mut ctx.accounts.reputation, &mut ctx.accounts.community_reputation, 0, points, Clock::get()?.unix_timestamp)?;
+    
+    Ok(())
+}
+
+pub fn get_treasury_balance(
+    ctx: Context<GetTreasuryBalance>,
+) -> Result<u64> {
+    Ok(ctx.accounts.treasury_token_account.amount)
+}
+
+pub fn initialize_reward_pool(
+    ctx: Context<InitializeRewardPool>,
+    reward_rate: u64,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.reward_pool;
+    pool.governance = ctx.accounts.governance.key();
+    pool.reward_mint = ctx.accounts.reward_mint.key();
+    pool.vault = ctx.accounts.reward_vault.key();
+    pool.reward_rate = reward_rate;
+    pool.total_funded = 0;
+    pool.total_claimed = 0;
+    pool.bump = ctx.bumps.reward_pool;
+    Ok(())
+}
+
+pub fn claim_rewards(
+    ctx: Context<ClaimRewards>,
+    epoch: u64,
+) -> Result<()> {
+    // Rewards become claimable once their epoch is over
+    let current_time = Clock::get()?.unix_timestamp;
+    if epoch >= ctx.accounts.governance.epoch_at(current_time) {
+        return Err(GovernanceError::EpochNotEnded.into());
+    }
+    
+    if ctx.accounts.activity_reward.is_claimed {
+        return Err(GovernanceError::RewardAlreadyClaimed.into());
+    }
+    
+    let payout = ctx.accounts.activity_reward.amount
+        .checked_mul(ctx.accounts.reward_pool.reward_rate)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    // Pay out from the pool vault, signed by the reward pool PDA
+    let transfer_ix = anchor_spl::token::Transfer {
+        from: ctx.accounts.reward_vault.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        authority: ctx.accounts.reward_pool.to_account_info(),
+    };
+    
+    let pool_seeds = &[
+        b"reward_pool".as_ref(),
+        ctx.accounts.reward_pool.governance.as_ref(),
+        &[ctx.accounts.reward_pool.bump],
+    ];
+    
+    let signer_seeds = &[&pool_seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+        signer_seeds,
+    );
+    
+    anchor_spl::token::transfer(cpi_ctx, payout)?;
+    
+    let reward = &mut ctx.accounts.activity_reward;
+    reward.is_claimed = true;
+    
+    let pool = &mut ctx.accounts.reward_pool;
+    pool.total_claimed = pool.total_claimed.checked_add(payout)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+pub fn initialize_conviction_voting(
+    ctx: Context<InitializeConvictionVoting>,
+    decay_bps: u64,
+    max_ratio_bps: u64,
+    weight_bps: u64,
+    step_seconds: i64,
+    min_effective_weight: u64,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::ManageTreasury) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    if decay_bps >= 10_000 || max_ratio_bps == 0 || max_ratio_bps > 10_000 || step_seconds <= 0 {
+        return Err(GovernanceError::InvalidGovernanceConfig.into());
+    }
+    
+    let config = &mut ctx.accounts.conviction_config;
+    config.community = ctx.accounts.community.key();
+    config.decay_bps = decay_bps;
+    config.max_ratio_bps = max_ratio_bps;
+    config.weight_bps = weight_bps;
+    config.step_seconds = step_seconds;
+    config.min_effective_weight = min_effective_weight;
+    config.total_staked = 0;
+    config.request_count = 0;
+    
+    Ok(())
+}
+
+pub fn create_funding_request(
+    ctx: Context<CreateFundingRequest>,
+    description: String,
+    requested_amount: u64,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::CreateProposal) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    if requested_amount == 0 {
+        return Err(GovernanceError::InvalidAmount.into());
+    }
+    
+    let current_time = Clock::get()?.unix_timestamp;
+    let request = &mut ctx.accounts.funding_request;
+    request.community = ctx.accounts.community.key();
+    request.treasury = ctx.accounts.treasury.key();
+    request.requester = ctx.accounts.authority.key();
+    request.beneficiary = ctx.accounts.beneficiary.key();
+    request.description = description;
+    request.requested_amount = requested_amount;
+    request.staked_weight = 0;
+    request.conviction = 0;
+    request.last_updated = current_time;
+    request.status = FundingStatus::Active;
+    request.created_at = current_time;
+    request.funded_at = None;
+    
+    let config = &mut ctx.accounts.conviction_config;
+    config.request_count = config.request_count.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+pub fn stake_conviction(
+    ctx: Context<StakeConviction>,
+    weight: u64,
+) -> Result<()> {
+    if weight == 0 {
+        return Err(GovernanceError::InvalidAmount.into());
+    }
+    
+    let request = &mut ctx.accounts.funding_request;
+    if request.status != FundingStatus::Active {
+        return Err(GovernanceError::FundingRequestNotActive.into());
+    }
+    
+    // Stakes across all requests are capped by the member's voting power
+    let membership = &mut ctx.accounts.membership;
+    let staked = membership.conviction_staked.checked_add(weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    if staked > membership.voting_power {
+        return Err(GovernanceError::InsufficientVotingPower.into());
+    }
+    membership.conviction_staked = staked;
+    
+    // Accrue conviction under the old stake before changing it
+    let config = &mut ctx.accounts.conviction_config;
+    let current_time = Clock::get()?.unix_timestamp;
+    accrue_conviction(request, config, current_time)?;
+    
+    request.staked_weight = request.staked_weight.checked_add(weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    config.total_staked = config.total_staked.checked_add(weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    let stake = &mut ctx.accounts.stake;
+    stake.request = request.key();
+    stake.user = ctx.accounts.authority.key();
+    stake.weight = stake.weight.checked_add(weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    stake.last_updated = current_time;
+    
+    Ok(())
+}
+
+pub fn unstake_conviction(
+    ctx: Context<UnstakeConviction>,
+    weight: u64,
+) -> Result<()> {
+    if weight == 0 {
+        return Err(GovernanceError::InvalidAmount.into());
+    }
+    
+    let current_time = Clock::get()?.unix_timestamp;
+    release_stake(
+        &mut ctx.accounts.funding_request,
+        &mut ctx.accounts.conviction_config,
+        &mut ctx.accounts.stake,
+        &mut ctx.accounts.membership,
+        weight,
+        current_time,
+    )
+}
+
+// Releases stakes of removed or deactivated members so they stop accruing conviction, callable by anyone
+pub fn release_conviction_stake(
+    ctx: Context<ReleaseConvictionStake>,
+) -> Result<()> {
+    let weight = ctx.accounts.stake.weight;
+    if weight == 0 {
+        return Err(GovernanceError::InvalidAmount.into());
+    }
+    
+    let current_time = Clock::get()?.unix_timestamp;
+    release_stake(
+        &mut ctx.accounts.funding_request,
+        &mut ctx.accounts.conviction_config,
+        &mut ctx.accounts.stake,
+        &mut ctx.accounts.membership,
+        weight,
+        current_time,
+    )
+}
+
+// Takes weight off a stake, accruing the request's conviction under the old stake first
+fn release_stake(
+    request: &mut FundingRequest,
+    config: &mut ConvictionConfig,
+    stake: &mut ConvictionStake,
+    membership: &mut Membership,
+    weight: u64,
+    current_time: i64,
+) -> Result<()> {
+    // Funded and canceled requests no longer count towards the staked total
+    if request.status == FundingStatus::Active {
+        accrue_conviction(request, config, current_time)?;
+        config.total_staked = config.total_staked.checked_sub(weight)
+            .ok_or(GovernanceError::MathOverflow)?;
+    }
+    
+    request.staked_weight = request.staked_weight.checked_sub(weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    stake.weight = stake.weight.checked_sub(weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    stake.last_updated = current_time;
+    
+    membership.conviction_staked = membership.conviction_staked.checked_sub(weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+pub fn update_conviction(
+    ctx: Context<UpdateConviction>,
+) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let request = &mut ctx.accounts.funding_request;
+    let config = &mut ctx.accounts.conviction_config;
+    
+    if request.status != FundingStatus::Active {
+        return Err(GovernanceError::FundingRequestNotActive.into());
+    }
+    
+    accrue_conviction(request, config, current_time)?;
+    
+    // Pay out automatically once conviction crosses the threshold for the current treasury balance
+    let threshold = conviction_threshold(config, request.requested_amount, ctx.accounts.treasury_token_account.amount);
+    let passed = matches!(threshold, Some(threshold) if request.conviction as u128 >= threshold);
+    if !passed {
+        return Ok(());
+    }
+    
+    let treasury = &mut ctx.accounts.treasury;
+    let transfer_ix = anchor_spl::token::Transfer {
+        from: ctx.accounts.treasury_token_account.to_account_info(),
+        to: ctx.accounts.beneficiary.to_account_info(),
+        authority: treasury.to_account_info(),
+    };
+    
+    let treasury_seeds = &[
+        b"treasury".as_ref(),
+        treasury.community.as_ref(),
+        treasury.mint.as_ref(),
+        &[treasury.bump],
+    ];
+    
+    let signer_seeds = &[&treasury_seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+        signer_seeds,
+    );
+    
+    anchor_spl::token::transfer(cpi_ctx, request.requested_amount)?;
+    
+    treasury.total_spent = treasury.total_spent.checked_add(request.requested_amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    // Stakes on a funded request stop counting, members unstake to reuse their power
+    config.total_staked = config.total_staked.checked_sub(request.staked_weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    request.status = FundingStatus::Funded;
+    request.funded_at = Some(current_time);
+    
+    Ok(())
+}
+
+pub fn cancel_funding_request(
+    ctx: Context<CancelFundingRequest>,
+) -> Result<()> {
+    let request = &mut ctx.accounts.funding_request;
+    if request.status != FundingStatus::Active {
+        return Err(GovernanceError::FundingRequestNotActive.into());
+    }
+    
+    let config = &mut ctx.accounts.conviction_config;
+    config.total_staked = config.total_staked.checked_sub(request.staked_weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    request.status = FundingStatus::Canceled;
+    
+    Ok(())
+}
+
+pub fn cancel_proposal(
+    ctx: Context<CancelProposal>,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    
+    // Only active proposals can be withdrawn by their proposer
+    if proposal.status != ProposalStatus::Active {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    // Cancellation is only possible before voting concludes
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time > proposal.end_time {
+        return Err(GovernanceError::VotingEnded.into());
+    }
+    
+    proposal.status = ProposalStatus::Canceled;
+    proposal.canceled_by = Some(ctx.accounts.authority.key());
+    proposal.canceled_at = Some(current_time);
+    
+    Ok(())
+}
+
+pub fn initialize_curator_council(
+    ctx: Context<InitializeCuratorCouncil>,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::ManageMembers) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    // Curators are appointed and the threshold set through executed proposals
+    let council = &mut ctx.accounts.council;
+    council.community = ctx.accounts.community.key();
+    council.curators = Vec::new();
+    council.created_at = Clock::get()?.unix_timestamp;
+    
+    Ok(())
+}
+
+pub fn approve_as_curator(
+    ctx: Context<ApproveAsCurator>,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    if proposal.status != ProposalStatus::Active || proposal.council_threshold == 0 {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    if !ctx.accounts.council.curators.contains(&ctx.accounts.authority.key()) {
+        return Err(GovernanceError::NotCurator.into());
+    }
+    
+    let approval = &mut ctx.accounts.approval;
+    approval.proposal = proposal.key();
+    approval.curator = ctx.accounts.authority.key();
+    approval.approved_at = Clock::get()?.unix_timestamp;
+    
+    if proposal.council_approvers.len() >= MAX_CURATORS {
+        return Err(GovernanceError::TooManyApprovers.into());
+    }
+    proposal.council_approvers.push(ctx.accounts.authority.key());
+    
+    Ok(())
+}
+
+pub fn veto_proposal(
+    ctx: Context<VetoProposal>,
+) -> Result<()> {
+    let membership = &ctx.accounts.membership;
+    let community = &ctx.accounts.community;
+    let proposal = &mut ctx.accounts.proposal;
+    
+    // Only community admins and moderators can veto
+    if !membership.role.has_permission(Permission::Moderate) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    // Proposals can be vetoed while voting or after success, but never once executed
+    if proposal.status != ProposalStatus::Active && proposal.status != ProposalStatus::Succeeded {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    // Veto must happen within the community's window after voting ends
+    let current_time = Clock::get()?.unix_timestamp;
+    let veto_deadline = proposal.reveal_end_time.checked_add(community.veto_window)
+        .ok_or(GovernanceError::MathOverflow)?;
+    if current_time > veto_deadline {
+        return Err(GovernanceError::VetoWindowClosed.into());
+    }
+    
+    proposal.status = ProposalStatus::Vetoed;
+    proposal.canceled_by = Some(ctx.accounts.authority.key());
+    proposal.canceled_at = Some(current_time);
+    
+    let governance = &ctx.accounts.governance;
+    penalize_reputation(&mut ctx.accounts.proposer_reputation, &mut ctx.accounts.proposer_community_reputation, governance, governance.veto_penalty, current_time)?;
+    
+    Ok(())
+}
+
+pub fn settle_proposal_deposit(
+    ctx: Context<SettleProposalDeposit>,
+) -> Result<()> {
+    let community = &ctx.accounts.community;
+    let proposal = &mut ctx.accounts.proposal;
+    let amount = proposal.deposit_amount;
+    
+    if amount == 0 {
+        return Err(GovernanceError::DepositAlreadySettled.into());
+    }
+    
+    // Deposits are refunded once quorum was reached or the proposer canceled before any votes, vetoed proposals forfeit them
+    let current_time = Clock::get()?.unix_timestamp;
+    let veto_deadline = proposal.reveal_end_time.checked_add(community.veto_window)
+        .ok_or(GovernanceError::MathOverflow)?;
+    let refund = match proposal.status {
+        ProposalStatus::Draft | ProposalStatus::Active => {
+            return Err(GovernanceError::InvalidProposalState.into());
+        },
+        // Succeeded proposals can still be vetoed as spam
+        ProposalStatus::Succeeded if current_time <= veto_deadline => {
+            return Err(GovernanceError::InvalidProposalState.into());
+        },
+        ProposalStatus::Canceled => proposal.vote_count == 0,
+        ProposalStatus::Vetoed => false,
+        _ => proposal.quorum_reached,
+    };
+    
+    let vault_authority_seeds = &[
+        b"vault_authority".as_ref(),
+        proposal.community.as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer_seeds = &[&vault_authority_seeds[..]];
+    
+    match proposal.deposit_mint {
+        None if refund => {
+            **proposal.to_account_info().try_borrow_mut_lamports()? -= amount;
+            **ctx.accounts.proposer.to_account_info().try_borrow_mut_lamports()? += amount;
+        },
+        None => match (
+            ctx.accounts.treasury.as_mut(),
+            ctx.accounts.treasury_token_account.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+        ) {
+            // Forfeited SOL is wrapped into the community's native mint treasury
+            (Some(treasury), Some(treasury_token_account), Some(token_program)) => {
+                if treasury.community != proposal.community
+                    || treasury.mint != anchor_spl::token::spl_token::native_mint::ID
+                    || treasury_token_account.key() != treasury.token_account
+                {
+                    return Err(GovernanceError::InvalidDepositAccount.into());
+                }
+                
+                **proposal.to_account_info().try_borrow_mut_lamports()? -= amount;
+                **treasury_token_account.to_account_info().try_borrow_mut_lamports()? += amount;
+                
+                let sync_ix = anchor_spl::token::SyncNative {
+                    account: treasury_token_account.to_account_info(),
+                };
+                anchor_spl::token::sync_native(CpiContext::new(token_program.to_account_info(), sync_ix))?;
+                
+                treasury.total_contributed = treasury.total_contributed.checked_add(amount)
+                    .ok_or(GovernanceError::MathOverflow)?;
+            },
+            // Communities without a native mint treasury keep forfeited SOL on their PDA
+            _ => {
+                if !ctx.accounts.native_treasury.data_is_empty() {
+                    return Err(GovernanceError::InvalidDepositAccount.into());
+                }
+                **proposal.to_account_info().try_borrow_mut_lamports()? -= amount;
+                **community.to_account_info().try_borrow_mut_lamports()? += amount;
+            },
+        },
+        Some(mint) => {
+            let (Some(deposit_vault), Some(token_program)) = (
+                ctx.accounts.deposit_vault.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+            ) else {
+                return Err(GovernanceError::InvalidDepositAccount.into());
+            };
+            
+            let destination = if refund {
+                let Some(proposer_deposit_account) = ctx.accounts.proposer_deposit_account.as_ref() else {
+                    return Err(GovernanceError::InvalidDepositAccount.into());
+                };
+                if proposer_deposit_account.owner != proposal.proposer || proposer_deposit_account.mint != mint {
+                    return Err(GovernanceError::InvalidDepositAccount.into());
+                }
+                proposer_deposit_account.to_account_info()
+            } else {
+                let (Some(treasury), Some(treasury_token_account)) = (
+                    ctx.accounts.treasury.as_mut(),
+                    ctx.accounts.treasury_token_account.as_ref(),
+                ) else {
+                    return Err(GovernanceError::InvalidDepositAccount.into());
+                };
+                if treasury.community != proposal.community
+                    || treasury.mint != mint
+                    || treasury_token_account.key() != treasury.token_account
+                {
+                    return Err(GovernanceError::InvalidDepositAccount.into());
+                }
+                treasury.total_contributed = treasury.total_contributed.checked_add(amount)
+                    .ok_or(GovernanceError::MathOverflow)?;
+                treasury_token_account.to_account_info()
+            };
+            
+            let transfer_ix = anchor_spl::token::Transfer {
+                from: deposit_vault.to_account_info(),
+                to: destination,
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), transfer_ix, signer_seeds);
+            anchor_spl::token::transfer(cpi_ctx, amount)?;
+            
+            // Return the vault's rent to the proposer who paid it
+            let close_ix = anchor_spl::token::CloseAccount {
+                account: deposit_vault.to_account_info(),
+                destination: ctx.accounts.proposer.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), close_ix, signer_seeds);
+            anchor_spl::token::close_account(cpi_ctx)?;
+        },
+    }
+    
+    proposal.deposit_amount = 0;
+    
+    Ok(())
+}
+
+pub fn create_faction(
+    ctx: Context<CreateFaction>,
+    name: String,
+    description: String,
+    investment_strategy: String,
+) -> Result<()> {
+    let faction = &mut ctx.accounts.faction;
+    
+    faction.governance = ctx.accounts.governance.key();
+    faction.name = name;
+    faction.description = description;
+    faction.founder = ctx.accounts.authority.key();
+    faction.member_count = 1; // Founder is the first member
+    faction.creation_time = Clock::get()?.unix_timestamp;
+    faction.investment_strategy = investment_strategy;
+    faction.is_active = true;
+    faction.admin_count = 1;
+    faction.admin_version = 0;
+    
+    // Create membership for faction founder
+    let membership = &mut ctx.accounts.membership;
+    membership.faction = faction.key();
+    membership.user = ctx.accounts.authority.key();
+    membership.join_time = Clock::get()?.unix_timestamp;
+    membership.is_active = true;
+    membership.role = MemberRole::Admin; // Founder administers the faction
+    membership.delegation_count = 0;
+    
+    Ok(())
+}
+
+pub fn join_faction(
+    ctx: Context<JoinFaction>,
+) -> Result<()> {
+    let faction = &mut ctx.accounts.faction;
+    let membership = &mut ctx.accounts.membership;
+    if membership.is_active {
+        return Err(GovernanceError::AlreadyFactionMember.into());
+    }
+    
+    // Removed members rejoin through their existing membership, keeping any delegations not yet released
+    membership.faction = faction.key();
+    membership.user = ctx.accounts.user.key();
+    membership.join_time = Clock::get()?.unix_timestamp;
+    membership.is_active = true;
+    membership.role = MemberRole::Member;
+    
+    // Increment faction member count
+    faction.member_count = faction.member_count.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+pub fn leave_faction(
+    ctx: Context<LeaveFaction>,
+) -> Result<()> {
+    let faction = &mut ctx.accounts.faction;
+    if ctx.accounts.user.key() == faction.founder {
+        return Err(GovernanceError::FactionFounderProtected.into());
+    }
+    
+    if ctx.accounts.membership.delegation_count > 0 {
+        return Err(GovernanceError::FactionDelegationActive.into());
+    }
+    
+    // Faction membership account is closed to the user by the account constraint,
+    // removed members were already taken off the member count
+    let membership = &ctx.accounts.membership;
+    if membership.is_active {
+        update_faction_admins(faction, membership.role == MemberRole::Admin, false)?;
+        faction.member_count = faction.member_count.checked_sub(1)
+            .ok_or(GovernanceError::MathOverflow)?;
+    }
+    
+    Ok(())
+}
+
+// Keeps the admin count in step with a member's role, voiding spend approvals given by the old admins
+fn update_faction_admins(faction: &mut StrategicFaction, was_admin: bool, is_admin: bool) -> Result<()> {
+    if was_admin == is_admin {
+        return Ok(());
+    }
+    
+    if is_admin {
+        faction.admin_count = faction.admin_count.checked_add(1)
+            .ok_or(GovernanceError::MathOverflow)?;
+        if faction.admin_count as usize > MAX_FACTION_ADMINS {
+            return Err(GovernanceError::TooManyFactionAdmins.into());
+        }
+    } else {
+        faction.admin_count = faction.admin_count.checked_sub(1)
+            .ok_or(GovernanceError::MathOverflow)?;
+    }
+    faction.admin_version = faction.admin_version.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+pub fn set_faction_role(
+    ctx: Context<ManageFactionMember>,
+    role: MemberRole,
+) -> Result<()> {
+    // Only the founder appoints and dismisses admins
+    let faction = &mut ctx.accounts.faction;
+    if ctx.accounts.authority.key() != faction.founder {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    let member = &mut ctx.accounts.member_membership;
+    if member.user == faction.founder {
+        return Err(GovernanceError::FactionFounderProtected.into());
+    }
+    
+    update_faction_admins(faction, member.role == MemberRole::Admin, role == MemberRole::Admin)?;
+    member.role = role;
+    
+    Ok(())
+}
+
+pub fn remove_faction_member(
+    ctx: Context<ManageFactionMember>,
+) -> Result<()> {
+    if ctx.accounts.admin_membership.role != MemberRole::Admin {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    let faction = &mut ctx.accounts.faction;
+    let member = &mut ctx.accounts.member_membership;
+    if !member.is_active {
+        return Err(GovernanceError::NotFactionMember.into());
+    }
+    if member.user == faction.founder {
+        return Err(GovernanceError::FactionFounderProtected.into());
+    }
+    
+    // Admins are dismissed by the founder only
+    let was_admin = member.role == MemberRole::Admin;
+    if was_admin && ctx.accounts.authority.key() != faction.founder {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    update_faction_admins(faction, was_admin, false)?;
+    
+    // Delegations of removed members can then be released by anyone
+    member.is_active = false;
+    member.role = MemberRole::Member;
+    
+    faction.member_count = faction.member_count.checked_sub(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+pub fn delegate_to_faction(
+    ctx: Context<DelegateToFaction>,
+) -> Result<()> {
+    let membership = &mut ctx.accounts.membership;
+    if membership.delegated_to.is_some() {
+        return Err(GovernanceError::VotingPowerDelegated.into());
+    }
+    
+    let faction_key = ctx.accounts.faction.key();
+    let community_key = ctx.accounts.community.key();
+    let weight = membership.voting_power;
+    membership.delegated_to = Some(faction_key);
+    
+    let delegation = &mut ctx.accounts.delegation;
+    delegation.faction = faction_key;
+    delegation.community = community_key;
+    delegation.user = ctx.accounts.user.key();
+    delegation.weight = weight;
+    delegation.delegated_at = Clock::get()?.unix_timestamp;
+    
+    let bloc = &mut ctx.accounts.bloc;
+    bloc.faction = faction_key;
+    bloc.community = community_key;
+    bloc.delegated_weight = bloc.delegated_weight.checked_add(weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    bloc.delegator_count = bloc.delegator_count.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    let faction_membership = &mut ctx.accounts.faction_membership;
+    faction_membership.delegation_count = faction_membership.delegation_count.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    let current_time = delegation.delegated_at;
+    checkpoint_membership(&mut ctx.accounts.power_checkpoints, membership, current_time);
+    checkpoint_bloc(&mut ctx.accounts.bloc_checkpoints, bloc, current_time);
+    fit_checkpoints(&ctx.accounts.power_checkpoints, ctx.accounts.user.to_account_info(), ctx.accounts.system_program.to_account_info())?;
+    fit_checkpoints(&ctx.accounts.bloc_checkpoints, ctx.accounts.user.to_account_info(), ctx.accounts.system_program.to_account_info())?;
+    
+    Ok(())
+}
+
+pub fn undelegate_from_faction(
+    ctx: Context<UndelegateFromFaction>,
+) -> Result<()> {
+    // Delegation account is closed to the user by the account constraint
+    release_delegation(
+        &mut ctx.accounts.bloc,
+        &ctx.accounts.delegation,
+        &mut ctx.accounts.membership,
+        &mut ctx.accounts.faction_membership,
+        &mut ctx.accounts.power_checkpoints,
+        &mut ctx.accounts.bloc_checkpoints,
+    )?;
+    fit_checkpoints(&ctx.accounts.power_checkpoints, ctx.accounts.user.to_account_info(), ctx.accounts.system_program.to_account_info())?;
+    fit_checkpoints(&ctx.accounts.bloc_checkpoints, ctx.accounts.user.to_account_info(), ctx.accounts.system_program.to_account_info())?;
+    
+    Ok(())
+}
+
+// Returns delegations of removed faction members to them, callable by anyone
+pub fn release_faction_delegation(
+    ctx: Context<ReleaseFactionDelegation>,
+) -> Result<()> {
+    // Delegation account is closed to its owner by the account constraint
+    release_delegation(
+        &mut ctx.accounts.bloc,
+        &ctx.accounts.delegation,
+        &mut ctx.accounts.membership,
+        &mut ctx.accounts.faction_membership,
+        &mut ctx.accounts.power_checkpoints,
+        &mut ctx.accounts.bloc_checkpoints,
+    )?;
+    fit_checkpoints(&ctx.accounts.power_checkpoints, ctx.accounts.authority.to_account_info(), ctx.accounts.system_program.to_account_info())?;
+    fit_checkpoints(&ctx.accounts.bloc_checkpoints, ctx.accounts.authority.to_account_info(), ctx.accounts.system_program.to_account_info())?;
+    
+    Ok(())
+}
+
+// Takes a delegation's weight off the faction's bloc and hands the voting power back to the member
+fn release_delegation(
+    bloc: &mut FactionBloc,
+    delegation: &FactionDelegation,
+    membership: &mut Membership,
+    faction_membership: &mut FactionMembership,
+    power_checkpoints: &mut VotingPowerCheckpoints,
+    bloc_checkpoints: &mut VotingPowerCheckpoints,
+) -> Result<()> {
+    // Weight the bloc has cast stays delegated until those votes have ended
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time <= bloc.locked_until {
+        return Err(GovernanceError::DelegationLocked.into());
+    }
+    
+    bloc.delegated_weight = bloc.delegated_weight.checked_sub(delegation.weight)
+        .ok_or(GovernanceError::MathOverflow)?;
+    bloc.delegator_count = bloc.delegator_count.checked_sub(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    membership.delegated_to = None;
+    
+    faction_membership.delegation_count = faction_membership.delegation_count.checked_sub(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    checkpoint_membership(power_checkpoints, membership, current_time);
+    checkpoint_bloc(bloc_checkpoints, bloc, current_time);
+    
+    Ok(())
+}
+
+pub fn cast_faction_member_vote(
+    ctx: Context<CastFactionMemberVote>,
+    vote_type: VoteType,
+) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+    let current_time = Clock::get()?.unix_timestamp;
+    if proposal.status != ProposalStatus::Active || current_time > proposal.end_time {
+        return Err(GovernanceError::VotingEnded.into());
+    }
+    
+    let faction_vote = &mut ctx.accounts.faction_vote;
+    if faction_vote.is_cast {
+        return Err(GovernanceError::AlreadyVoted.into());
+    }
+    
+    // Members weigh in with the power they delegated to the bloc before the proposal started
+    if ctx.accounts.delegation.delegated_at >= proposal.start_time {
+        return Err(GovernanceError::NoVotingPowerSnapshot.into());
+    }
+    let weight = ctx.accounts.delegation.weight;
+    faction_vote.faction = ctx.accounts.faction.key();
+    faction_vote.proposal = proposal.key();
+    match vote_type {
+        VoteType::For => {
+            faction_vote.for_weight = faction_vote.for_weight.checked_add(weight)
+                .ok_or(GovernanceError::MathOverflow)?;
+        },
+        VoteType::Against => {
+            faction_vote.against_weight = faction_vote.against_weight.checked_add(weight)
+                .ok_or(GovernanceError::MathOverflow)?;
+        },
+        VoteType::Abstain => {
+            faction_vote.abstain_weight = faction_vote.abstain_weight.checked_add(weight)
+                .ok_or(GovernanceError::MathOverflow)?;
+        },
+    }
+    
+    let member_vote = &mut ctx.accounts.member_vote;
+    member_vote.faction_vote = faction_vote.key();
+    member_vote.user = ctx.accounts.user.key();
+    member_vote.vote_type = vote_type;
+    member_vote.weight = weight;
+    member_vote.vote_time = current_time;
+    
+    Ok(())
+}
+
+pub fn cast_faction_vote(
+    ctx: Context<CastFactionVote>,
+) -> Result<()> {
+    if ctx.accounts.admin_membership.role != MemberRole::Admin {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    let proposal = &mut ctx.accounts.proposal;
+    if proposal.status != ProposalStatus::Active {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    // Bloc weight is linear voting power, cast as an open binary vote
+    if proposal.ballot_type != BallotType::Binary
+        || proposal.is_secret_ballot
+        || proposal.voting_mode != VotingMode::Linear
+    {
+        return Err(GovernanceError::InvalidBallot.into());
+    }
+    
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time > proposal.end_time {
+        return Err(GovernanceError::VotingEnded.into());
+    }
+    
+    let faction_vote = &mut ctx.accounts.faction_vote;
+    if faction_vote.is_cast {
+        return Err(GovernanceError::AlreadyVoted.into());
+    }
+    
+    // The internal majority decides the bloc's vote, a tie abstains
+    let vote_type = if faction_vote.for_weight > faction_vote.against_weight {
+        VoteType::For
+    } else if faction_vote.against_weight > faction_vote.for_weight {
+        VoteType::Against
+    } else {
+        VoteType::Abstain
+    };
+    
+    // The bloc casts the weight delegated to it when the proposal started
+    let bloc_weight = ctx.accounts.bloc_checkpoints.power_at(proposal.start_time)
+        .map(|snapshot| snapshot.voting_power)
+        .ok_or(GovernanceError::NoVotingPowerSnapshot)?;
+    let bloc = &mut ctx.accounts.bloc;
+    tally_vote(proposal, vote_type, bloc_weight)?;
+    proposal.vote_count = proposal.vote_count.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    faction_vote.is_cast = true;
+    faction_vote.cast_vote_type = Some(vote_type);
+    faction_vote.cast_weight = bloc_weight;
+    
+    if bloc.locked_until < proposal.reveal_end_time {
+        bloc.locked_until = proposal.reveal_end_time;
+    }
+    
+    Ok(())
+}
+
+pub fn create_faction_treasury(
+    ctx: Context<CreateFactionTreasury>,
+) -> Result<()> {
+    if ctx.accounts.membership.role != MemberRole::Admin {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.faction = ctx.accounts.faction.key();
+    treasury.mint = ctx.accounts.mint.key();
+    treasury.token_account = ctx.accounts.treasury_token_account.key();
+    treasury.total_contributed = 0;
+    treasury.total_spent = 0;
+    treasury.bump = ctx.bumps.treasury;
+    treasury.spend_count = 0;
+    
+    Ok(())
+}
+
+pub fn contribute_to_faction_treasury(
+    ctx: Context<ContributeToFactionTreasury>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Err(GovernanceError::InvalidAmount.into());
+    }
+    
+    let transfer_ix = anchor_spl::token::Transfer {
+        from: ctx.accounts.contributor_token_account.to_account_info(),
+        to: ctx.accounts.treasury_token_account.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+    };
+    
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+    );
+    
+    anchor_spl::token::transfer(cpi_ctx, amount)?;
+    
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.total_contributed = treasury.total_contributed.checked_add(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+// Proposes a treasury spend, counting the proposing admin's approval
+pub fn propose_faction_spend(
+    ctx: Context<ProposeFactionSpend>,
+    amount: u64,
+) -> Result<()> {
+    if ctx.accounts.membership.role != MemberRole::Admin {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    if amount == 0 {
+        return Err(GovernanceError::InvalidAmount.into());
+    }
+    
+    let treasury = &mut ctx.accounts.treasury;
+    let spend = &mut ctx.accounts.spend;
+    spend.treasury = treasury.key();
+    spend.destination = ctx.accounts.destination_token_account.key();
+    spend.amount = amount;
+    spend.admin_version = ctx.accounts.faction.admin_version;
+    spend.approvers = vec![ctx.accounts.authority.key()];
+    spend.created_at = Clock::get()?.unix_timestamp;
+    
+    treasury.spend_count = treasury.spend_count.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+pub fn approve_faction_spend(
+    ctx: Context<ApproveFactionSpend>,
+) -> Result<()> {
+    if ctx.accounts.membership.role != MemberRole::Admin {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    let spend = &mut ctx.accounts.spend;
+    if spend.admin_version != ctx.accounts.faction.admin_version {
+        return Err(GovernanceError::FactionAdminsChanged.into());
+    }
+    
+    let approver = ctx.accounts.authority.key();
+    if spend.approvers.contains(&approver) {
+        return Err(GovernanceError::AlreadyVoted.into());
+    }
+    if spend.approvers.len() >= MAX_FACTION_ADMINS {
+        return Err(GovernanceError::TooManyApprovers.into());
+    }
+    spend.approvers.push(approver);
+    
+    Ok(())
+}
+
+// Pays out a spend once a majority of the current admins approved it
+pub fn spend_faction_treasury(
+    ctx: Context<SpendFactionTreasury>,
+) -> Result<()> {
+    if ctx.accounts.membership.role != MemberRole::Admin {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    let faction = &ctx.accounts.faction;
+    let spend = &ctx.accounts.spend;
+    if spend.admin_version != faction.admin_version {
+        return Err(GovernanceError::FactionAdminsChanged.into());
+    }
+    let approvals = spend.approvers.len() as u64;
+    if approvals.checked_mul(2).ok_or(GovernanceError::MathOverflow)? <= faction.admin_count {
+        return Err(GovernanceError::FactionSpendNotApproved.into());
+    }
+    let amount = spend.amount;
+    
+    let treasury = &mut ctx.accounts.treasury;
+    let transfer_ix = anchor_spl::token::Transfer {
+        from: ctx.accounts.treasury_token_account.to_account_info(),
+        to: ctx.accounts.destination_token_account.to_account_info(),
+        authority: treasury.to_account_info(),
+    };
+    
+    let treasury_seeds = &[
+        b"faction_treasury".as_ref(),
+        treasury.faction.as_ref(),
+        treasury.mint.as_ref(),
+        &[treasury.bump],
+    ];
+    
+    let signer_seeds = &[&treasury_seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+        signer_seeds,
+    );
+    
+    anchor_spl::token::transfer(cpi_ctx, amount)?;
+    
+    treasury.total_spent = treasury.total_spent.checked_add(amount)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+// Read one fresh price feed per holding quoted in the portfolio's base mint, in holding order
+fn read_price_feeds<'info>(
+    holdings: &[PortfolioHolding],
+    base_mint: &Pubkey,
+    price_feeds: &'info [AccountInfo<'info>],
+    current_time: i64,
+) -> Result<Vec<u64>> {
+    if price_feeds.len() != holdings.len() {
+        return Err(GovernanceError::InvalidPriceFeed.into());
+    }
+    
+    let mut prices = Vec::with_capacity(holdings.len());
+    for (holding, feed_info) in holdings.iter().zip(price_feeds.iter()) {
+        let feed = Account::<CollectionPriceFeed>::try_from(feed_info)?;
+        let feed_age = current_time.checked_sub(feed.updated_at).ok_or(GovernanceError::MathOverflow)?;
+        if feed.collection != holding.collection || feed.base_mint != *base_mint || feed_age > PRICE_FEED_MAX_AGE {
+            return Err(GovernanceError::InvalidPriceFeed.into());
+        }
+        prices.push(feed.price);
+    }
+    
+    Ok(prices)
+}
+
+// NAV is uninvested cash plus holdings valued at their collection prices
+fn portfolio_nav(holdings: &[PortfolioHolding], prices: &[u64], cash: u64) -> Result<u64> {
+    let mut nav = cash as u128;
+    for (holding, price) in holdings.iter().zip(prices.iter()) {
+        nav = nav.checked_add((holding.quantity as u128).checked_mul(*price as u128).ok_or(GovernanceError::MathOverflow)?)
+            .ok_or(GovernanceError::MathOverflow)?;
+    }
+    
+    u64::try_from(nav).map_err(|_| GovernanceError::MathOverflow.into())
+}
+
+// Checks the risk level and that allocations cover the targets and sum to 100
+fn validate_strategy(risk_level: u8, target_collections: &[Pubkey], allocation_percentages: &[u8]) -> Result<()> {
+    // Validate risk level
+    if risk_level == 0 || risk_level > 5 {
+        return Err(GovernanceError::InvalidInvestmentAllocation.into());
+    }
+    
+    // Validate allocation percentages
+    if target_collections.len() != allocation_percentages.len() {
+        return Err(GovernanceError::InvalidInvestmentAllocation.into());
+    }
+    
+    // Ensure allocation percentages sum to 100
+    let total_percentage: u32 = allocation_percentages.iter().map(|percentage| *percentage as u32).sum();
+    if total_percentage != 100 {
+        return Err(GovernanceError::InvalidInvestmentAllocation.into());
+    }
+    
+    Ok(())
+}
+
+pub fn create_investment_strategy(
+    ctx: Context<CreateInvestmentStrategy>,
+    name: String,
+    description: String,
+    risk_level: u8,
+    target_collections: Vec<Pubkey>,
+    allocation_percentages: Vec<u8>,
+) -> Result<()> {
+    validate_strategy(risk_level, &target_collections, &allocation_percentages)?;
+    
+    let strategy = &mut ctx.accounts.strategy;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    strategy.community = ctx.accounts.community.key();
+    strategy.name = name;
+    strategy.description = description;
+    strategy.creator = ctx.accounts.authority.key();
+    strategy.risk_level = risk_level;
+    strategy.target_collections = target_collections;
+    strategy.allocation_percentages = allocation_percentages;
+    strategy.creation_time = current_time;
+    strategy.last_updated = current_time;
+    strategy.performance_score = 0; // Initial performance score
+    strategy.is_active = true;
+    
+    // Update reputation for strategy creation
+    credit_reputation(&mut ctx.accounts.reputation, &mut ctx.accounts.community_reputation, 30, 0, current_time)?;
+    
+    Ok(())
+}
+
+pub fn create_strategy_portfolio(
+    ctx: Context<CreateStrategyPortfolio>,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::ManageTreasury) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    let strategy = &ctx.accounts.strategy;
+    let portfolio = &mut ctx.accounts.portfolio;
+    portfolio.strategy = strategy.key();
+    portfolio.community = strategy.community;
+    portfolio.base_mint = ctx.accounts.base_mint.key();
+    portfolio.cash_account = ctx.accounts.portfolio_token_account.key();
+    portfolio.total_funded = 0;
+    portfolio.cash = 0;
+    portfolio.holdings = strategy.target_collections.iter()
+        .map(|collection| PortfolioHolding { collection: *collection, asset_account: Pubkey::default(), quantity: 0 })
+        .collect();
+    portfolio.last_nav = 0;
+    portfolio.last_valued_at = 0;
+    portfolio.reputation_credit = 0;
+    portfolio.bump = ctx.bumps.portfolio;
+    
+    Ok(())
+}
+
+// Sets the token account holding a collection's fraction tokens, before the portfolio holds any
+pub fn set_holding_asset(
+    ctx: Context<SetHoldingAsset>,
+    collection: Pubkey,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::ManageTreasury) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    let asset_account = ctx.accounts.asset_account.key();
+    let holding = ctx.accounts.portfolio.holdings.iter_mut()
+        .find(|holding| holding.collection == collection)
+        .ok_or(GovernanceError::InvalidInvestmentAllocation)?;
+    if holding.quantity > 0 {
+        return Err(GovernanceError::InvalidInvestmentAllocation.into());
+    }
+    
+    holding.asset_account = asset_account;
+    
+    Ok(())
+}
+
+pub fn update_strategy_nav<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateStrategyNav<'info>>,
+) -> Result<()> {
+    let portfolio = &mut ctx.accounts.portfolio;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    let prices = read_price_feeds(&portfolio.holdings, &portfolio.base_mint, ctx.remaining_accounts, current_time)?;
+    let nav = portfolio_nav(&portfolio.holdings, &prices, portfolio.cash)?;
+    
+    portfolio.last_nav = nav;
+    portfolio.last_valued_at = current_time;
+    
+    // Performance is the return on funded capital in basis points
+    let performance = if portfolio.total_funded == 0 {
+        0
+    } else {
+        let gain = nav as i128 - portfolio.total_funded as i128;
+        i64::try_from(gain * 10_000 / portfolio.total_funded as i128).map_err(|_| GovernanceError::MathOverflow)?
+    };
+    ctx.accounts.strategy.performance_score = performance;
+    
+    // The creator holds one investment point per percent of return, adjusted as performance moves
+    let target_credit = performance / 100;
+    // Losses are capped by the points the creator holds, so only the applied change is recorded
+    let delta = target_credit - portfolio.reputation_credit;
+    let applied = credit_reputation(&mut ctx.accounts.creator_reputation, &mut ctx.accounts.creator_community_reputation, delta, 0, current_time)?;
+    portfolio.reputation_credit = portfolio.reputation_credit.checked_add(applied)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+pub fn compute_rebalance<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ComputeRebalance<'info>>,
+) -> Result<()> {
+    let strategy = &ctx.accounts.strategy;
+    let portfolio = &ctx.accounts.portfolio;
+    let plan = &mut ctx.accounts.rebalance_plan;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // The creator fills the orders, so only they or treasury managers price them
+    if ctx.accounts.authority.key() != strategy.creator
+        && !ctx.accounts.membership.role.has_permission(Permission::ManageTreasury)
+    {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    // A plan up for approval, or approved with unfilled orders, is only replaced once it expired
+    let expires_at = plan.computed_at.checked_add(REBALANCE_PLAN_MAX_AGE)
+        .ok_or(GovernanceError::MathOverflow)?;
+    let pending = plan.frozen || (plan.approved && plan.orders.iter().any(|order| !order.filled));
+    if pending && current_time <= expires_at {
+        return Err(GovernanceError::RebalancePending.into());
+    }
+    
+    let prices = read_price_feeds(&portfolio.holdings, &portfolio.base_mint, ctx.remaining_accounts, current_time)?;
+    let nav = portfolio_nav(&portfolio.holdings, &prices, portfolio.cash)?;
+    
+    // Orders move each holding to its target share of NAV
+    let mut orders = Vec::new();
+    for ((holding, price), percentage) in portfolio.holdings.iter()
+        .zip(prices.iter())
+        .zip(strategy.allocation_percentages.iter())
+    {
+        if *price == 0 {
+            continue;
+        }
+        
+        let target_value = (nav as u128) * (*percentage as u128) / 100;
+        let target_quantity = u64::try_from(target_value / (*price as u128)).map_err(|_| GovernanceError::MathOverflow)?;
+        
+        let (side, quantity) = if target_quantity > holding.quantity {
+            (RebalanceSide::Buy, target_quantity - holding.quantity)
+        } else if target_quantity < holding.quantity {
+            (RebalanceSide::Sell, holding.quantity - target_quantity)
+        } else {
+            continue;
+        };
+        
+        orders.push(RebalanceOrder {
+            collection: holding.collection,
+            side,
+            quantity,
+            price: *price,
+            filled: false,
+        });
+    }
+    
+    plan.portfolio = portfolio.key();
+    plan.strategy = strategy.key();
+    plan.community = portfolio.community;
+    plan.orders = orders;
+    plan.nav = nav;
+    plan.computed_at = current_time;
+    plan.approved = false;
+    plan.bump = ctx.bumps.rebalance_plan;
+    plan.frozen = false;
+    
+    Ok(())
+}
+
+// Fills an approved order: the strategy creator trades the collection's fraction tokens with the portfolio at the approved price
+pub fn fill_rebalance_order(
+    ctx: Context<FillRebalanceOrder>,
+    order_index: u8,
+) -> Result<()> {
+    let plan = &mut ctx.accounts.rebalance_plan;
+    if !plan.approved {
+        return Err(GovernanceError::RebalanceNotApproved.into());
+    }
+    
+    // Orders are priced when the plan is computed, stale prices can no longer be traded at
+    let plan_age = Clock::get()?.unix_timestamp.checked_sub(plan.computed_at)
+        .ok_or(GovernanceError::MathOverflow)?;
+    if plan_age > REBALANCE_PLAN_MAX_AGE {
+        return Err(GovernanceError::RebalancePlanExpired.into());
+    }
+    
+    let order = plan.orders.get_mut(order_index as usize)
+        .ok_or(GovernanceError::InvalidRebalanceOrder)?;
+    if order.filled {
+        return Err(GovernanceError::InvalidRebalanceOrder.into());
+    }
+    
+    let portfolio = &mut ctx.accounts.portfolio;
+    let holding_index = portfolio.holdings.iter()
+        .position(|holding| holding.collection == order.collection)
+        .ok_or(GovernanceError::InvalidRebalanceOrder)?;
+    
+    // Units are delivered into the holding's asset account, owned by the portfolio
+    let asset_account = &ctx.accounts.portfolio_asset_account;
+    if asset_account.key() != portfolio.holdings[holding_index].asset_account
+        || ctx.accounts.creator_asset_account.mint != asset_account.mint
+    {
+        return Err(GovernanceError::InvalidRebalanceOrder.into());
+    }
+    
+    let cost = order.quantity.checked_mul(order.price)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    let strategy_key = ctx.accounts.strategy.key();
+    let portfolio_seeds = &[
+        b"strategy_portfolio".as_ref(),
+        strategy_key.as_ref(),
+        &[portfolio.bump],
+    ];
+    let signer = &[&portfolio_seeds[..]];
+    
+    match order.side {
+        RebalanceSide::Buy => {
+            // The creator delivers the units, then the portfolio pays for them
+            let deliver_ix = anchor_spl::token::Transfer {
+                from: ctx.accounts.creator_asset_account.to_account_info(),
+                to: ctx.accounts.portfolio_asset_account.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            };
+            anchor_spl::token::transfer(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), deliver_ix),
+                order.quantity,
+            )?;
+            
+            let pay_ix = anchor_spl::token::Transfer {
+                from: ctx.accounts.portfolio_token_account.to_account_info(),
+                to: ctx.accounts.creator_token_account.to_account_info(),
+                authority: portfolio.to_account_info(),
+            };
+            anchor_spl::token::transfer(
+                CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), pay_ix, signer),
+                cost,
+            )?;
+            
+            portfolio.cash = portfolio.cash.checked_sub(cost)
+                .ok_or(GovernanceError::MathOverflow)?;
+            let holding = &mut portfolio.holdings[holding_index];
+            holding.quantity = holding.quantity.checked_add(order.quantity)
+                .ok_or(GovernanceError::MathOverflow)?;
+        },
+        RebalanceSide::Sell => {
+            // The creator pays for the units, then the portfolio releases them
+            let pay_ix = anchor_spl::token::Transfer {
+                from: ctx.accounts.creator_token_account.to_account_info(),
+                to: ctx.accounts.portfolio_token_account.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            };
+            anchor_spl::token::transfer(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), pay_ix),
+                cost,
+            )?;
+            
+            let deliver_ix = anchor_spl::token::Transfer {
+                from: ctx.accounts.portfolio_asset_account.to_account_info(),
+                to: ctx.accounts.creator_asset_account.to_account_info(),
+                authority: portfolio.to_account_info(),
+            };
+            anchor_spl::token::transfer(
+                CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), deliver_ix, signer),
+                order.quantity,
+            )?;
+            
+            portfolio.cash = portfolio.cash.checked_add(cost)
+                .ok_or(GovernanceError::MathOverflow)?;
+            let holding = &mut portfolio.holdings[holding_index];
+            holding.quantity = holding.quantity.checked_sub(order.quantity)
+                .ok_or(GovernanceError::MathOverflow)?;
+        },
+    }
+    
+    // Recorded units must be backed by the asset account's balance
+    ctx.accounts.portfolio_asset_account.reload()?;
+    if ctx.accounts.portfolio_asset_account.amount < portfolio.holdings[holding_index].quantity {
+        return Err(GovernanceError::InvalidRebalanceOrder.into());
+    }
+    
+    order.filled = true;
+    
+    Ok(())
+}
+
+// Drops the remaining orders of a plan so a new one can be computed
+pub fn cancel_rebalance_plan(
+    ctx: Context<CancelRebalancePlan>,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::ManageTreasury) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    let plan = &mut ctx.accounts.rebalance_plan;
+    plan.orders.clear();
+    plan.approved = false;
+    
+    Ok(())
+}
+
+// Participant communities are passed as remaining accounts, in participant order
+pub fn create_federated_proposal<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateFederatedProposal<'info>>,
+    title: String,
+    description: String,
+    participants: Vec<FederatedParticipantTerms>,
+    min_approvals: u8,
+    duration: i64,
+) -> Result<()> {
+    if !ctx.accounts.membership.role.has_permission(Permission::CreateProposal) {
+        return Err(GovernanceError::InsufficientPermission.into());
+    }
+    
+    // The proposer's community takes part, required communities count towards the approvals
+    let required_count = participants.iter().filter(|terms| terms.required).count();
+    let proposer_participates = participants.iter().any(|terms| terms.community == ctx.accounts.community.key());
+    let unique = participants.iter().enumerate()
+        .all(|(index, terms)| participants[..index].iter().all(|other| other.community != terms.community));
+    if participants.len() < 2
+        || participants.len() > MAX_FEDERATED_COMMUNITIES
+        || !proposer_participates
+        || !unique
+        || (min_approvals as usize) < required_count.max(1)
+        || min_approvals as usize > participants.len()
+        || duration <= 0
+        || ctx.remaining_accounts.len() != participants.len()
+    {
+        return Err(GovernanceError::InvalidFederation.into());
+    }
+    
+    // Only active communities of this governance can take part
+    for (terms, community_info) in participants.iter().zip(ctx.remaining_accounts.iter()) {
+        let community = Account::<Community>::try_from(community_info)?;
+        if community.key() != terms.community
+            || community.governance != ctx.accounts.governance.key()
+            || !community.is_active
+        {
+            return Err(GovernanceError::InvalidFederation.into());
+        }
+    }
+    
+    let governance = &mut ctx.accounts.governance;
+    let federated_proposal = &mut ctx.accounts.federated_proposal;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    federated_proposal.governance = governance.key();
+    federated_proposal.proposer = ctx.accounts.authority.key();
+    federated_proposal.title = title;
+    federated_proposal.description = description;
+    federated_proposal.mint = ctx.accounts.mint.key();
+    federated_proposal.destination = ctx.accounts.destination_token_account.key();
+    federated_proposal.participants = participants.into_iter()
+        .map(|terms| FederatedParticipant {
+            community: terms.community,
+            required: terms.required,
+            contribution: terms.contribution,
+            decision: FederatedDecision::Pending,
+            is_funded: false,
+        })
+        .collect();
+    federated_proposal.min_approvals = min_approvals;
+    federated_proposal.status = FederatedStatus::Voting;
+    federated_proposal.created_at = current_time;
+    federated_proposal.deadline = current_time.checked_add(duration)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    governance.proposal_count = governance.proposal_count.checked_add(1)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+pub fn finalize_federated_proposal(
+    ctx: Context<FinalizeFederatedProposal>,
+) -> Result<()> {
+    let federated_proposal = &mut ctx.accounts.federated_proposal;
+    
+    if federated_proposal.status != FederatedStatus::Voting {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    // Communities that have not approved by the deadline count as rejecting
+    let current_time = Clock::get()?.unix_timestamp;
+    let expired = current_time > federated_proposal.deadline;
+    let participants = &federated_proposal.participants;
+    let approvals = participants.iter()
+        .filter(|participant| participant.decision == FederatedDecision::Approved)
+        .count();
+    let pending = if expired {
+        0
+    } else {
+        participants.iter().filter(|participant| participant.decision == FederatedDecision::Pending).count()
+    };
+    let required_rejected = participants.iter()
+        .any(|participant| participant.required && expired && participant.decision == FederatedDecision::Pending);
+    let required_approved = participants.iter()
+        .all(|participant| !participant.required || participant.decision == FederatedDecision::Approved);
+    
+    if required_approved && approvals >= federated_proposal.min_approvals as usize {
+        federated_proposal.status = FederatedStatus::Approved;
+    } else if required_rejected || approvals + pending < federated_proposal.min_approvals as usize {
+        federated_proposal.status = FederatedStatus::Rejected;
+    } else {
+        return Err(GovernanceError::FederationUndecided.into());
+    }
+    
+    Ok(())
+}
+
+// Draws an approving community's contribution from its treasury, callable by anyone once approved
+pub fn draw_federated_contribution(
+    ctx: Context<DrawFederatedContribution>,
+) -> Result<()> {
+    let federated_proposal = &mut ctx.accounts.federated_proposal;
+    
+    if federated_proposal.status != FederatedStatus::Approved {
+        return Err(GovernanceError::InvalidProposalState.into());
+    }
+    
+    let treasury = &mut ctx.accounts.treasury;
+    let participant = federated_proposal.participants.iter_mut()
+        .find(|participant| participant.community == treasury.community)
+        .ok_or(GovernanceError::InvalidFederation)?;
+    if participant.decision != FederatedDecision::Approved || participant.is_funded {
+        return Err(GovernanceError::InvalidFederation.into());
+    }
+    
+    pay_from_treasury(
+        treasury,
+        &ctx.accounts.treasury_token_account,
+        &ctx.accounts.destination_token_account,
+        &ctx.accounts.token_program,
+        participant.contribution,
+    )?;
+    participant.is_funded = true;
+    
+    Ok(())
+}
+
+// Tallies votes members signed off chain, submitted in a batch by a relayer that pays all rent.
+// Remaining accounts hold, per vote: membership, power checkpoints, reputation and the vote PDA,
+// which is created here like an on-chain vote. Signed votes earn no participation points or activity rewards.
+pub fn submit_signed_votes<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SubmitSignedVotes<'info>>,
+    votes: Vec<SignedVote>,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let governance = &ctx.accounts.governance;
+    
+    // Signed votes cover open, binary proposals
+    if proposal.ballot_type != BallotType::Binary || proposal.is_secret_ballot {
+        return Err(GovernanceError::InvalidBallot.into());
+    }
+    
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time > proposal.end_time {
+        return Err(GovernanceError::VotingEnded.into());
+    }
+    
+    if current_time < proposal.start_time {
+        return Err(GovernanceError::VotingNotStarted.into());
+    }
+    
+    if votes.is_empty() || ctx.remaining_accounts.len() != votes.len() * 4 {
+        return Err(GovernanceError::InvalidSignedVote.into());
+    }
+    
+    // The signatures are verified by an ed25519 instruction placed right before this one
+    let instructions = ctx.accounts.instructions.to_account_info();
+    let current_index = load_current_index_checked(&instructions)? as usize;
+    if current_index == 0 {
+        return Err(GovernanceError::InvalidSignedVote.into());
+    }
+    let signatures = verified_ed25519_signatures(&load_instruction_at_checked(current_index - 1, &instructions)?)?;
+    
+    let proposal_key = proposal.key();
+    for (signed_vote, accounts) in votes.iter().zip(ctx.remaining_accounts.chunks(4)) {
+        let voter = signed_vote.voter;
+        let message = signed_vote_message(&proposal_key, signed_vote.vote_type);
+        if !signatures.iter().any(|(signer, signed)| *signer == voter && *signed == message) {
+            return Err(GovernanceError::InvalidSignedVote.into());
+        }
+        
+        // Replay protection: the member's vote PDA must not exist yet, whether from a signed or an on-chain vote
+        let (vote_key, vote_bump) = Pubkey::find_program_address(&[b"vote".as_ref(), proposal_key.as_ref(), voter.as_ref()], ctx.program_id);
+        if accounts[3].key() != vote_key || !accounts[3].data_is_empty() {
+            return Err(GovernanceError::AlreadyVoted.into());
+        }
+        
+        let mut membership = Account::<Membership>::try_from(&accounts[0])?;
+        if membership.user != voter || membership.community != proposal.community || !membership.is_active {
+            return Err(GovernanceError::NotCommunityMember.into());
+        }
+        
+        let (checkpoints_key, _) = Pubkey::find_program_address(
+            &[b"power_checkpoints".as_ref(), proposal.community.as_ref(), voter.as_ref()],
+            ctx.program_id,
+        );
+        if accounts[1].key() != checkpoints_key {
+            return Err(GovernanceError::InvalidSignedVote.into());
+        }
+        let checkpoints = Account::<VotingPowerCheckpoints>::try_from(&accounts[1])?;
+        
+        let reputation = Account::<ReputationScore>::try_from(&accounts[2])?;
+        if reputation.user != voter || reputation.governance != governance.key() {
+            return Err(GovernanceError::InvalidSignedVote.into());
+        }
+        
+        // Weighted like cast_vote, with pending decay applied to a copy of the reputation
+        let vote_weight = calculate_vote_weight(proposal, &checkpoints)?;
+        let mut reputation = (*reputation).clone();
+        decay_reputation(&mut reputation, governance, current_time)?;
+        let vote_weight = reputation_weighted(proposal, vote_weight, &reputation)?;
+        
+        // Deposits back this vote until voting on the proposal ends
+        if membership.deposit_locked_until < proposal.reveal_end_time {
+            membership.deposit_locked_until = proposal.reveal_end_time;
+            membership.exit(ctx.program_id)?;
+        }
+        
+        tally_vote(proposal, signed_vote.vote_type, vote_weight)?;
+        
+        // Record the vote in the same PDA cast_vote would create, sized the same way
+        let vote_seeds = &[b"vote".as_ref(), proposal_key.as_ref(), voter.as_ref(), &[vote_bump]];
+        create_pda_account(
+            &ctx.accounts.relayer,
+            &accounts[3],
+            &ctx.accounts.system_program,
+            &[&vote_seeds[..]],
+            8 + 32 + 32 + 1 + 8 + 8 + 4 + 33 + 1,
+            ctx.program_id,
+        )?;
+        let vote = Vote {
+            proposal: proposal_key,
+            voter,
+            vote_type: signed_vote.vote_type,
+            voting_power: vote_weight,
+            vote_time: current_time,
+            choices: Vec::new(),
+            commitment: None,
+            is_revealed: true,
+        };
+        let mut vote_data = accounts[3].try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut vote_data;
+        vote.try_serialize(&mut writer)?;
+    }
+    
+    proposal.signed_vote_count = proposal.signed_vote_count.checked_add(votes.len() as u32)
+        .ok_or(GovernanceError::MathOverflow)?;
+    proposal.vote_count = proposal.vote_count.checked_add(votes.len() as u32)
+        .ok_or(GovernanceError::MathOverflow)?;
+    
+    Ok(())
+}
+
+// Creates a rent-exempt program account at a PDA, topping up any lamports already sent to the address
+fn create_pda_account<'info>(
+    payer: &Signer<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    signer_seeds: &[&[&[u8]]],
+    space: usize,
+    owner: &Pubkey,
+) -> Result<()> {
+    let rent_due = Rent::get()?.minimum_balance(space);
+    if account.lamports() == 0 {
+        let create_ix = anchor_lang::system_program::CreateAccount {
+            from: payer.to_account_info(),
+            to: account.clone(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(system_program.to_account_info(), create_ix, signer_seeds);
+        return anchor_lang::system_program::create_account(cpi_ctx, rent_due, space as u64, owner);
+    }
+    
+    let top_up = rent_due.saturating_sub(account.lamports());
+    if top_up > 0 {
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: payer.to_account_info(),
+            to: account.clone(),
+        };
+        anchor_lang::system_program::transfer(CpiContext::new(system_program.to_account_info(), transfer_ix), top_up)?;
+    }
+    
+    let allocate_ix = anchor_lang::system_program::Allocate { account_to_allocate: account.clone() };
+    anchor_lang::system_program::allocate(
+        CpiContext::new_with_signer(system_program.to_account_info(), allocate_ix, signer_seeds),
+        space as u64,
+    )?;
+    
+    let assign_ix = anchor_lang::system_program::Assign { account_to_assign: account.clone() };
+    anchor_lang::system_program::assign(
+        CpiContext::new_with_signer(system_program.to_account_info(), assign_ix, signer_seeds),
+        owner,
+    )
+}
+
+// Records a member's current voting power, for members who joined before checkpoints were kept
+pub fn checkpoint_voting_power(
+    ctx: Context<CheckpointVotingPower>,
+) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let checkpoints = &mut ctx.accounts.power_checkpoints;
+    checkpoint_membership(checkpoints, &ctx.accounts.membership, current_time);
+    let (community_key, user_key) = (ctx.accounts.community.key(), ctx.accounts.authority.key());
+    checkpoint_escrow(checkpoints, community_key, user_key, ctx.accounts.vote_escrow.as_deref(), current_time);
+    fit_checkpoints(&ctx.accounts.power_checkpoints, ctx.accounts.authority.to_account_info(), ctx.accounts.system_program.to_account_info())?;
+    
+    Ok(())
+}
+
+// Account contexts for the instructions
+
+#[derive(Accounts)]
+#[instruction(name: String, min_vote_threshold: u64, voting_period: i64, execution_delay: i64, execution_window: i64, reward_epoch_duration: i64)]
+pub struct InitializeGovernance<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 4 + name.len() + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 2 + 8 + 8 + 8 + 1 + 8 + 8,
+        seeds = [b"governance".as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGovernanceConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    // Closed once applied, its rent goes to whoever applies it
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"config_amendment".as_ref(),
+            governance.key().as_ref(),
+            governance.config_version.to_le_bytes().as_ref(),
+            config_amendment.update.hash().as_ref(),
+        ],
+        bump,
+        constraint = config_amendment.governance == governance.key()
+    )]
+    pub config_amendment: Account<'info, ConfigAmendment>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCommunityRecognition<'info> {
+    #[account(
+        mut,
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump,
+        constraint = governance.authority == authority.key()
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"community".as_ref(), governance.key().as_ref(), community.name.as_bytes()],
+        bump
+    )]
+    pub community: Account<'info, Community>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshReputation<'info> {
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"reputation".as_ref(), governance.key().as_ref(), reputation.user.as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, ReputationScore>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterReputation<'info> {
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        constraint = community.governance == governance.key()
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8,
+        seeds = [b"reputation".as_ref(), governance.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, ReputationScore>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8,
+        seeds = [b"community_reputation".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub community_reputation: Account<'info, CommunityReputation>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetReputation<'info> {
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        seeds = [b"reputation".as_ref(), governance.key().as_ref(), reputation.user.as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, ReputationScore>,
+    
+    // Only required to include a community breakdown, together with its community
+    #[account(
+        constraint = community.governance == governance.key() @ GovernanceError::InvalidCommunityReputation
+    )]
+    pub community: Option<Account<'info, Community>>,
+    
+    #[account(
+        constraint = community_reputation.user == reputation.user
+    )]
+    pub community_reputation: Option<Account<'info, CommunityReputation>>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String, description: String, veto_window: i64, voting_mode: VotingMode, reveal_period: i64, vote_tipping: VoteTipping)]
+pub struct CreateCommunity<'info> {
+    #[account(
+        mut,
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump,
+        constraint = governance.is_active
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 4 + name.len() + 4 + description.len() + 32 + 8 + 8 + 8 + 1 + 8 + 1 + 33 + 8 + 8 + 1 + 8 + 8 + 8 + 33 + 41 + 1 + 1 + 1,
+        seeds = [b"community".as_ref(), governance.key().as_ref(), name.as_bytes()],
+        bump
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 1 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 33 + 33 + 1,
+        seeds = [b"membership".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 4 + INITIAL_POWER_CHECKPOINTS * POWER_CHECKPOINT_SIZE,
+        seeds = [b"power_checkpoints".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub power_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8,
+        seeds = [b"reputation".as_ref(), governance.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, ReputationScore>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8,
+        seeds = [b"community_reputation".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub community_reputation: Account<'info, CommunityReputation>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinCommunity<'info> {
+    #[account(
+        constraint = governance.key() == community.governance
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        mut,
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 1 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 33 + 33 + 1,
+        seeds = [b"membership".as_ref(), community.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 4 + INITIAL_POWER_CHECKPOINTS * POWER_CHECKPOINT_SIZE,
+        seeds = [b"power_checkpoints".as_ref(), community.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub power_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8,
+        seeds = [b"reputation".as_ref(), community.governance.as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, ReputationScore>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8,
+        seeds = [b"community_reputation".as_ref(), community.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub community_reputation: Account<'info, CommunityReputation>,
+    
+    // Only required by the matching membership requirement
+    pub requirement_collection: Option<Account<'info, CollectionRating>>,
+    
+    pub requirement_vault: Option<Account<'info, FractionalVault>>,
+    
+    pub requirement_token_account: Option<Account<'info, anchor_spl::token::TokenAccount>>,
+    
+    /// CHECK: Token metadata of the held NFT, validated against its PDA and owner
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+    
+    // Only required by invite-only communities
+    #[account(
+        mut,
+        seeds = [b"invite".as_ref(), community.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub invite: Option<Account<'info, MembershipInvite>>,
+    
+    #[account(mut)]
+    pub inviter: Option<SystemAccount<'info>>,
+    
+    // Only required by application-review communities
+    #[account(
+        mut,
+        seeds = [b"application".as_ref(), community.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub application: Option<Account<'info, MembershipApplication>>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(invitee: Pubkey, expires_at: i64)]
+pub struct IssueInvite<'info> {
+    #[account(
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 32 + 8 + 8,
+        seeds = [b"invite".as_ref(), community.key().as_ref(), invitee.as_ref()],
+        bump
+    )]
+    pub invite: Account<'info, MembershipInvite>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeInvite<'info> {
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        mut,
+        close = inviter,
+        seeds = [b"invite".as_ref(), community.key().as_ref(), invite.invitee.as_ref()],
+        bump
+    )]
+    pub invite: Account<'info, MembershipInvite>,
+    
+    #[account(
+        mut,
+        constraint = inviter.key() == invite.inviter
+    )]
+    pub inviter: SystemAccount<'info>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(message: String)]
+pub struct ApplyToCommunity<'info> {
+    #[account(
+        constraint = community.is_active,
+        constraint = community.join_policy == JoinPolicy::Application
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        init,
+        payer = applicant,
+        space = 8 + 32 + 32 + 4 + message.len() + 1 + 8 + 33 + 9,
+        seeds = [b"application".as_ref(), community.key().as_ref(), applicant.key().as_ref()],
+        bump
+    )]
+    pub application: Account<'info, MembershipApplication>,
+    
+    #[account(mut)]
+    pub applicant: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReviewApplication<'info> {
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        mut,
+        seeds = [b"application".as_ref(), community.key().as_ref(), application.applicant.as_ref()],
+        bump
+    )]
+    pub application: Account<'info, MembershipApplication>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawApplication<'info> {
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        mut,
+        close = applicant,
+        seeds = [b"application".as_ref(), community.key().as_ref(), applicant.key().as_ref()],
+        bump
+    )]
+    pub application: Account<'info, MembershipApplication>,
+    
+    #[account(mut)]
+    pub applicant: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMembershipPolicy<'info> {
+    #[account(
+        mut,
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RecheckMembership<'info> {
+    #[account(
+        constraint = governance.key() == community.governance
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(mut)]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        mut,
+        seeds = [b"membership".as_ref(), community.key().as_ref(), member_membership.user.as_ref()],
+        bump,
+        constraint = member_membership.is_active
+    )]
+    pub member_membership: Account<'info, Membership>,
+    
+    #[account(
+        seeds = [b"reputation".as_ref(), community.governance.as_ref(), member_membership.user.as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, ReputationScore>,
+    
+    // Only required by the matching membership requirement
+    pub requirement_collection: Option<Account<'info, CollectionRating>>,
+    
+    pub requirement_vault: Option<Account<'info, FractionalVault>>,
+    
+    pub requirement_token_account: Option<Account<'info, anchor_spl::token::TokenAccount>>,
+    
+    /// CHECK: Token metadata of the held NFT, validated against its PDA and owner
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+    
+    // Anyone can crank re-checks
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(max_escrow_lock: i64)]
+pub struct CreateVotingVault<'info> {
+    #[account(
+        mut,
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    pub voting_mint: Account<'info, anchor_spl::token::Mint>,
+    
+    /// CHECK: PDA holding authority over the community's member vaults, stores no data
+    #[account(
+        seeds = [b"vault_authority".as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = voting_mint,
+        token::authority = vault_authority,
+        seeds = [b"voting_vault".as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub voting_vault: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetProposalDeposit<'info> {
+    #[account(
+        mut,
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct DepositVotingTokens<'info> {
+    #[account(
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        mut,
+        seeds = [b"membership".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 4 + INITIAL_POWER_CHECKPOINTS * POWER_CHECKPOINT_SIZE,
+        seeds = [b"power_checkpoints".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub power_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        mut,
+        seeds = [b"voting_vault".as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub voting_vault: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.mint == voting_vault.mint,
+        constraint = user_token_account.owner == authority.key()
+    )]
+    pub user_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct WithdrawVotingTokens<'info> {
+    #[account(
+        seeds = [b"community".as_ref(), community.governance.as_ref(), community.name.as_bytes()],
+        bump
+    )]
+    pub community: Account<'info, Community>,
+    
+    // Removed (inactive) members can still withdraw their deposits
+    #[account(
+        mut,
+        seeds = [b"membership".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 4 + INITIAL_POWER_CHECKPOINTS * POWER_CHECKPOINT_SIZE,
+        seeds = [b"power_checkpoints".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub power_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    /// CHECK: PDA holding authority over the community's member vaults, stores no data
+    #[account(
+        seeds = [b"vault_authority".as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"voting_vault".as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub voting_vault: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.mint == voting_vault.mint,
+        constraint = user_token_account.owner == authority.key()
+    )]
+    pub user_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, unlock_time: i64)]
+pub struct LockEscrowTokens<'info> {
+    #[account(
+        mut,
+        constraint = community.is_active,
+        constraint = community.voting_mint == Some(voting_mint.key())
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        seeds = [b"membership".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    pub voting_mint: Account<'info, anchor_spl::token::Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 1 + 8 + 8 + 8,
+        seeds = [b"vote_escrow".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub vote_escrow: Account<'info, VoteEscrow>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 4 + INITIAL_POWER_CHECKPOINTS * POWER_CHECKPOINT_SIZE,
+        seeds = [b"power_checkpoints".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub power_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        mut,
+        seeds = [b"voting_vault".as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub voting_vault: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.mint == voting_mint.key(),
+        constraint = user_token_account.owner == authority.key()
+    )]
+    pub user_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawEscrowTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"community".as_ref(), community.governance.as_ref(), community.name.as_bytes()],
+        bump
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"vote_escrow".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = vote_escrow.user == authority.key()
+    )]
+    pub vote_escrow: Account<'info, VoteEscrow>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 4 + INITIAL_POWER_CHECKPOINTS * POWER_CHECKPOINT_SIZE,
+        seeds = [b"power_checkpoints".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub power_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    /// CHECK: PDA holding authority over the community's member vaults, stores no data
+    #[account(
+        seeds = [b"vault_authority".as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"voting_vault".as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub voting_vault: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.mint == voting_vault.mint,
+        constraint = user_token_account.owner == authority.key()
+    )]
+    pub user_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageMember<'info> {
+    #[account(mut)]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        seeds = [b"membership".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = admin_membership.is_active
+    )]
+    pub admin_membership: Account<'info, Membership>,
+    
+    #[account(
+        mut,
+        seeds = [b"membership".as_ref(), community.key().as_ref(), member_membership.user.as_ref()],
+        bump,
+        constraint = member_membership.is_active,
+        constraint = member_membership.user != authority.key()
+    )]
+    pub member_membership: Account<'info, Membership>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveCommunity<'info> {
+    #[account(mut)]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"membership".as_ref(), community.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(args: CreateProposalArgs, epoch: u64)]
+pub struct CreateProposal<'info> {
+    #[account(
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump,
+        constraint = governance.is_active
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        mut,
+        constraint = community.governance == governance.key(),
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 32 + 4 + args.title.len() + 4 + args.description.len() + 
+            1 + 8 + 8 + 1 + 8 + 8 + 8 + 9 + 1 + 33 + 9 + 33 + 9 + 9 + 1 + args.action.as_ref().map_or(0, ProposalAction::space) + 1 +
+            1 + 4 + args.options.iter().map(|label| 4 + label.len() + 8 + 1).sum::<usize>() + 8 + 2 + 1 + 8 + 8 + 33 + 1 + 1 +
+            4 + if community.council_threshold > 0 { MAX_CURATORS * 32 } else { 0 } + 1 + 4 + 8 + 8 + 4,
+        seeds = [b"proposal".as_ref(), community.key().as_ref(), community.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [b"reputation".as_ref(), community.governance.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, ReputationScore>,
+    
+    #[account(
+        mut,
+        seeds = [b"community_reputation".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub community_reputation: Account<'info, CommunityReputation>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 1,
+        seeds = [b"activity_reward".as_ref(), authority.key().as_ref(), governance.key().as_ref(), epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub activity_reward: Account<'info, ActivityReward>,
+    
+    // Only required when the community charges token deposits
+    pub deposit_mint: Option<Account<'info, anchor_spl::token::Mint>>,
+    
+    /// CHECK: PDA holding authority over the community's member vaults, stores no data
+    #[account(
+        seeds = [b"vault_authority".as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = deposit_mint,
+        token::authority = vault_authority,
+        seeds = [b"proposal_deposit".as_ref(), proposal.key().as_ref()],
+        bump
+    )]
+    pub deposit_vault: Option<Account<'info, anchor_spl::token::TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = proposer_deposit_account.owner == authority.key()
+    )]
+    pub proposer_deposit_account: Option<Account<'info, anchor_spl::token::TokenAccount>>,
+    
+    // Only required by ApproveRebalance actions
+    #[account(mut)]
+    pub rebalance_plan: Option<Account<'info, RebalancePlan>>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+#[instruction(vote_type: VoteType, epoch: u64)]
+pub struct CastVote<'info> {
+    #[account(
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump,
+        constraint = governance.is_active
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = proposal.status == ProposalStatus::Active
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        constraint = membership.community == proposal.community,
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        seeds = [b"power_checkpoints".as_ref(), proposal.community.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub power_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 1 + 8 + 8 + 4 + 33 + 1,
+        seeds = [b"vote".as_ref(), proposal.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    // Optional vote-escrow lock adding decaying voting power and reward boosts
+    #[account(
+        seeds = [b"vote_escrow".as_ref(), proposal.community.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub vote_escrow: Option<Account<'info, VoteEscrow>>,
+    
+    #[account(
+        mut,
+        seeds = [b"reputation".as_ref(), governance.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, ReputationScore>,
+    
+    #[account(
+        mut,
+        seeds = [b"community_reputation".as_ref(), proposal.community.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub community_reputation: Account<'info, CommunityReputation>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 1,
+        seeds = [b"activity_reward".as_ref(), authority.key().as_ref(), governance.key().as_ref(), epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub activity_reward: Account<'info, ActivityReward>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(choices: Vec<u8>, epoch: u64)]
+pub struct CastBallot<'info> {
+    #[account(
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump,
+        constraint = governance.is_active
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = proposal.status == ProposalStatus::Active
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        constraint = membership.community == proposal.community,
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        seeds = [b"power_checkpoints".as_ref(), proposal.community.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub power_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 1 + 8 + 8 + 4 + choices.len() + 33 + 1,
+        seeds = [b"vote".as_ref(), proposal.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    // Optional vote-escrow lock adding decaying voting power and reward boosts
+    #[account(
+        seeds = [b"vote_escrow".as_ref(), proposal.community.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub vote_escrow: Option<Account<'info, VoteEscrow>>,
+    
+    #[account(
+        mut,
+        seeds = [b"reputation".as_ref(), governance.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, ReputationScore>,
+    
+    #[account(
+        mut,
+        seeds = [b"community_reputation".as_ref(), proposal.community.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub community_reputation: Account<'info, CommunityReputation>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 1,
+        seeds = [b"activity_reward".as_ref(), authority.key().as_ref(), governance.key().as_ref(), epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub activity_reward: Account<'info, ActivityReward>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32])]
+pub struct CommitVote<'info> {
+    #[account(
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump,
+        constraint = governance.is_active
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = proposal.status == ProposalStatus::Active
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        constraint = membership.community == proposal.community,
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        seeds = [b"power_checkpoints".as_ref(), proposal.community.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub power_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        mut,
+        seeds = [b"reputation".as_ref(), governance.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, ReputationScore>,
+    
+    // Sized for the largest possible reveal
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 1 + 8 + 8 + 4 + MAX_PROPOSAL_OPTIONS + 33 + 1,
+        seeds = [b"vote".as_ref(), proposal.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(vote_type: VoteType, choices: Vec<u8>, salt: [u8; 32], epoch: u64)]
+pub struct RevealVote<'info> {
+    #[account(
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump,
+        constraint = governance.is_active
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = proposal.status == ProposalStatus::Active
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [b"vote".as_ref(), proposal.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = vote.voter == authority.key()
+    )]
+    pub vote: Account<'info, Vote>,
+    
+    // Optional vote-escrow lock adding decaying voting power and reward boosts
+    #[account(
+        seeds = [b"vote_escrow".as_ref(), proposal.community.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub vote_escrow: Option<Account<'info, VoteEscrow>>,
+    
+    #[account(
+        mut,
+        seeds = [b"reputation".as_ref(), governance.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, ReputationScore>,
+    
+    #[account(
+        mut,
+        seeds = [b"community_reputation".as_ref(), proposal.community.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub community_reputation: Account<'info, CommunityReputation>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 1,
+        seeds = [b"activity_reward".as_ref(), authority.key().as_ref(), governance.key().as_ref(), epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub activity_reward: Account<'info, ActivityReward>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(option_index: u8, instructions: Vec<ProposalInstruction>)]
+pub struct InsertProposalInstructions<'info> {
+    #[account(
+        mut,
+        constraint = proposal.proposer == authority.key(),
+        constraint = proposal.status == ProposalStatus::Active
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 1 + 4 + instructions.iter()
+            .map(|ix| 32 + 4 + ix.accounts.len() * (32 + 1 + 1) + 4 + ix.data.len())
+            .sum::<usize>(),
+        seeds = [b"proposal_instructions".as_ref(), proposal.key().as_ref(), &[option_index]],
+        bump
+    )]
+    pub proposal_instructions: Account<'info, ProposalInstructions>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeProposal<'info> {
+    #[account(
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump,
+        constraint = governance.is_active
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = proposal.status == ProposalStatus::Active
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        constraint = community.key() == proposal.community
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        mut,
+        seeds = [b"reputation".as_ref(), governance.key().as_ref(), proposal.proposer.as_ref()],
+        bump
+    )]
+    pub proposer_reputation: Account<'info, ReputationScore>,
+    
+    #[account(
+        mut,
+        seeds = [b"community_reputation".as_ref(), proposal.community.as_ref(), proposal.proposer.as_ref()],
+        bump
+    )]
+    pub proposer_community_reputation: Account<'info, CommunityReputation>,
+    
+    // Required when the proposal needs council approval
+    #[account(
+        seeds = [b"curator_council".as_ref(), proposal.community.as_ref()],
+        bump
+    )]
+    pub council: Option<Account<'info, CuratorCouncil>>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = proposal.status == ProposalStatus::Succeeded
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [b"community".as_ref(), community.governance.as_ref(), community.name.as_bytes()],
+        bump,
+        constraint = community.key() == proposal.community
+    )]
+    pub community: Account<'info, Community>,
+    
+    // Only required when the executed option has stored instructions
+    #[account(
+        seeds = [b"proposal_instructions".as_ref(), proposal.key().as_ref(), &[proposal_instructions.option_index]],
+        bump
+    )]
+    pub proposal_instructions: Option<Account<'info, ProposalInstructions>>,
+    
+    #[account(
+        mut,
+        seeds = [b"reputation".as_ref(), proposal.governance.as_ref(), proposal.proposer.as_ref()],
+        bump
+    )]
+    pub proposer_reputation: Account<'info, ReputationScore>,
+    
+    #[account(
+        mut,
+        seeds = [b"community_reputation".as_ref(), proposal.community.as_ref(), proposal.proposer.as_ref()],
+        bump
+    )]
+    pub proposer_community_reputation: Account<'info, CommunityReputation>,
+    
+    // Only required when the proposal carries a treasury transfer
+    #[account(mut)]
+    pub treasury: Option<Account<'info, CommunityTreasury>>,
+    
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, anchor_spl::token::TokenAccount>>,
+    
+    #[account(mut)]
+    pub destination_token_account: Option<Account<'info, anchor_spl::token::TokenAccount>>,
+    
+    // Only required by InvestInVault actions
+    pub vault: Option<Account<'info, FractionalVault>>,
+    
+    #[account(mut)]
+    pub fraction_treasury: Option<Account<'info, CommunityTreasury>>,
+    
+    #[account(mut)]
+    pub fraction_treasury_token_account: Option<Account<'info, anchor_spl::token::TokenAccount>>,
+    
+    #[account(mut)]
+    pub seller_fraction_account: Option<Account<'info, anchor_spl::token::TokenAccount>>,
+    
+    // Only required by VerifyCollection actions
+    pub rating_system: Option<Account<'info, RatingSystem>>,
+    
+    #[account(mut)]
+    pub collection: Option<Account<'info, CollectionRating>>,
+    
+    // Only required by AddStrategy actions
+    #[account(
+        init,
+        payer = authority,
+        space = proposal.action.as_ref().map_or(0, ProposalAction::strategy_space),
+        seeds = [
+            b"investment_strategy".as_ref(),
+            proposal.community.as_ref(),
+            proposal.action.as_ref().map_or("", ProposalAction::strategy_name).as_bytes(),
+        ],
+        bump
+    )]
+    pub strategy: Option<Account<'info, InvestmentStrategy>>,
+    
+    // Only required by RemoveMember actions
+    #[account(mut)]
+    pub member_membership: Option<Account<'info, Membership>>,
+    
+    // Only required by FundStrategy actions
+    #[account(mut)]
+    pub portfolio: Option<Account<'info, StrategyPortfolio>>,
+    
+    // Only required by UpdateAllocations actions
+    #[account(mut)]
+    pub target_strategy: Option<Account<'info, InvestmentStrategy>>,
+    
+    // Only required by ApproveRebalance actions
+    #[account(mut)]
+    pub rebalance_plan: Option<Account<'info, RebalancePlan>>,
+    
+    // Only required by ApproveFederated actions
+    #[account(mut)]
+    pub federated_proposal: Option<Account<'info, FederatedProposal>>,
+    
+    // Only required by UpdateConfig actions
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 8 + (9 + 9 + 2 + 3 + 9 + 9 + 9 + 2) + 4 + 8,
+        seeds = [
+            b"config_amendment".as_ref(),
+            governance.key().as_ref(),
+            governance.config_version.to_le_bytes().as_ref(),
+            proposal.action.as_ref().map_or([0; 32], ProposalAction::config_hash).as_ref(),
+        ],
+        bump
+    )]
+    pub config_amendment: Option<Account<'info, ConfigAmendment>>,
+    
+    // Each community approves an amendment once
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32,
+        seeds = [
+            b"amendment_approval".as_ref(),
+            governance.key().as_ref(),
+            governance.config_version.to_le_bytes().as_ref(),
+            proposal.action.as_ref().map_or([0; 32], ProposalAction::config_hash).as_ref(),
+            proposal.community.as_ref(),
+        ],
+        bump
+    )]
+    pub amendment_approval: Option<Account<'info, ConfigAmendmentApproval>>,
+    
+    // Only required by FundRewardPool actions
+    #[account(
+        mut,
+        seeds = [b"reward_pool".as_ref(), proposal.governance.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Option<Account<'info, RewardPool>>,
+    
+    // Only required by curator council actions
+    #[account(
+        mut,
+        seeds = [b"curator_council".as_ref(), proposal.community.as_ref()],
+        bump
+    )]
+    pub council: Option<Account<'info, CuratorCouncil>>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[derive(Accounts)]
+pub struct CreateTreasury<'info> {
+    #[account(
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    pub mint: Account<'info, anchor_spl::token::Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 32 + 8 + 8 + 1,
+        seeds = [b"treasury".as_ref(), community.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, CommunityTreasury>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint,
+        token::authority = treasury,
+        seeds = [b"treasury_token".as_ref(), treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct ContributeToTreasury<'info> {
+    #[account(
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury".as_ref(), community.key().as_ref(), treasury.mint.as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, CommunityTreasury>,
+    
+    #[account(
+        constraint = mint.key() == treasury.mint
+    )]
+    pub mint: Account<'info, anchor_spl::token::Mint>,
+    
+    #[account(
+        mut,
+        constraint = treasury_token_account.key() == treasury.token_account
+    )]
+    pub treasury_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = contributor_token_account.mint == treasury.mint,
+        constraint = contributor_token_account.owner == authority.key()
+    )]
+    pub contributor_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"reputation".as_ref(), community.governance.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, ReputationScore>,
+    
+    #[account(
+        mut,
+        seeds = [b"community_reputation".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub community_reputation: Account<'info, CommunityReputation>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct GetTreasuryBalance<'info> {
+    pub treasury: Account<'info, CommunityTreasury>,
+    
+    #[account(
+        constraint = treasury_token_account.key() == treasury.token_account
+    )]
+    pub treasury_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(reward_rate: u64)]
+pub struct InitializeRewardPool<'info> {
+    #[account(
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump,
+        constraint = governance.authority == authority.key()
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    pub reward_mint: Account<'info, anchor_spl::token::Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 1,
+        seeds = [b"reward_pool".as_ref(), governance.key().as_ref()],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = reward_mint,
+        token::authority = reward_pool,
+        seeds = [b"reward_vault".as_ref(), reward_pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct ClaimRewards<'info> {
+    #[account(
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"reward_pool".as_ref(), governance.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+    
+    #[account(
+        mut,
+        constraint = reward_vault.key() == reward_pool.vault
+    )]
+    pub reward_vault: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"activity_reward".as_ref(), authority.key().as_ref(), governance.key().as_ref(), epoch.to_le_bytes().as_ref()],
+        bump,
+        constraint = activity_reward.user == authority.key()
+    )]
+    pub activity_reward: Account<'info, ActivityReward>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.mint == reward_pool.reward_mint,
+        constraint = user_token_account.owner == authority.key()
+    )]
+    pub user_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(decay_bps: u64, max_ratio_bps: u64, weight_bps: u64, step_seconds: i64, min_effective_weight: u64)]
+pub struct InitializeConvictionVoting<'info> {
+    #[account(
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8,
+        seeds = [b"conviction_config".as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub conviction_config: Account<'info, ConvictionConfig>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(description: String, requested_amount: u64)]
+pub struct CreateFundingRequest<'info> {
+    #[account(
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        mut,
+        seeds = [b"conviction_config".as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub conviction_config: Account<'info, ConvictionConfig>,
+    
+    #[account(
+        constraint = treasury.community == community.key()
+    )]
+    pub treasury: Account<'info, CommunityTreasury>,
+    
+    #[account(
+        constraint = beneficiary.mint == treasury.mint
+    )]
+    pub beneficiary: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 32 + 32 + 4 + description.len() + 8 + 8 + 8 + 8 + 1 + 8 + 9,
+        seeds = [b"funding_request".as_ref(), community.key().as_ref(), conviction_config.request_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub funding_request: Account<'info, FundingRequest>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(weight: u64)]
+pub struct StakeConviction<'info> {
+    #[account(
+        mut,
+        seeds = [b"conviction_config".as_ref(), funding_request.community.as_ref()],
+        bump
+    )]
+    pub conviction_config: Account<'info, ConvictionConfig>,
+    
+    #[account(mut)]
+    pub funding_request: Account<'info, FundingRequest>,
+    
+    #[account(
+        mut,
+        seeds = [b"membership".as_ref(), funding_request.community.as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8,
+        seeds = [b"conviction_stake".as_ref(), funding_request.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, ConvictionStake>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(weight: u64)]
+pub struct UnstakeConviction<'info> {
+    #[account(
+        mut,
+        seeds = [b"conviction_config".as_ref(), funding_request.community.as_ref()],
+        bump
+    )]
+    pub conviction_config: Account<'info, ConvictionConfig>,
+    
+    #[account(mut)]
+    pub funding_request: Account<'info, FundingRequest>,
+    
+    // Removed members can still release their stakes
+    #[account(
+        mut,
+        seeds = [b"membership".as_ref(), funding_request.community.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        mut,
+        seeds = [b"conviction_stake".as_ref(), funding_request.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = stake.user == authority.key()
+    )]
+    pub stake: Account<'info, ConvictionStake>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseConvictionStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"conviction_config".as_ref(), funding_request.community.as_ref()],
+        bump
+    )]
+    pub conviction_config: Account<'info, ConvictionConfig>,
+    
+    #[account(mut)]
+    pub funding_request: Account<'info, FundingRequest>,
+    
+    // Only stakes of members who are no longer active can be released by others
+    #[account(
+        mut,
+        seeds = [b"membership".as_ref(), funding_request.community.as_ref(), stake.user.as_ref()],
+        bump,
+        constraint = !membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        mut,
+        seeds = [b"conviction_stake".as_ref(), funding_request.key().as_ref(), stake.user.as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, ConvictionStake>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConviction<'info> {
+    #[account(
+        mut,
+        seeds = [b"conviction_config".as_ref(), funding_request.community.as_ref()],
+        bump
+    )]
+    pub conviction_config: Account<'info, ConvictionConfig>,
+    
+    #[account(mut)]
+    pub funding_request: Account<'info, FundingRequest>,
+    
+    #[account(
+        mut,
+        constraint = treasury.key() == funding_request.treasury
+    )]
+    pub treasury: Account<'info, CommunityTreasury>,
+    
+    #[account(
+        mut,
+        constraint = treasury_token_account.key() == treasury.token_account
+    )]
+    pub treasury_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = beneficiary.key() == funding_request.beneficiary
+    )]
+    pub beneficiary: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelFundingRequest<'info> {
+    #[account(
+        mut,
+        seeds = [b"conviction_config".as_ref(), funding_request.community.as_ref()],
+        bump
+    )]
+    pub conviction_config: Account<'info, ConvictionConfig>,
+    
+    #[account(
+        mut,
+        constraint = funding_request.requester == authority.key()
+    )]
+    pub funding_request: Account<'info, FundingRequest>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    #[account(
+        mut,
+        constraint = proposal.proposer == authority.key(),
+        constraint = proposal.status == ProposalStatus::Active
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCuratorCouncil<'info> {
+    #[account(
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 4 + (MAX_CURATORS * 32) + 8,
+        seeds = [b"curator_council".as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub council: Account<'info, CuratorCouncil>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveAsCurator<'info> {
+    #[account(
+        seeds = [b"curator_council".as_ref(), proposal.community.as_ref()],
+        bump
+    )]
+    pub council: Account<'info, CuratorCouncil>,
+    
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 8,
+        seeds = [b"council_approval".as_ref(), proposal.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub approval: Account<'info, CouncilApproval>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VetoProposal<'info> {
+    #[account(
+        constraint = governance.key() == community.governance
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        constraint = community.key() == proposal.community
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [b"reputation".as_ref(), community.governance.as_ref(), proposal.proposer.as_ref()],
+        bump
+    )]
+    pub proposer_reputation: Account<'info, ReputationScore>,
+    
+    #[account(
+        mut,
+        seeds = [b"community_reputation".as_ref(), proposal.community.as_ref(), proposal.proposer.as_ref()],
+        bump
+    )]
+    pub proposer_community_reputation: Account<'info, CommunityReputation>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleProposalDeposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"community".as_ref(), community.governance.as_ref(), community.name.as_bytes()],
+        bump,
+        constraint = community.key() == proposal.community
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        constraint = proposer.key() == proposal.proposer
+    )]
+    pub proposer: SystemAccount<'info>,
+    
+    /// CHECK: PDA holding authority over the community's member vaults, stores no data
+    #[account(
+        seeds = [b"vault_authority".as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    // Only required for token deposits
+    #[account(
+        mut,
+        seeds = [b"proposal_deposit".as_ref(), proposal.key().as_ref()],
+        bump
+    )]
+    pub deposit_vault: Option<Account<'info, anchor_spl::token::TokenAccount>>,
+    
+    #[account(mut)]
+    pub proposer_deposit_account: Option<Account<'info, anchor_spl::token::TokenAccount>>,
+    
+    // Forfeited deposits go to this treasury, SOL falls back to the community PDA without one
+    #[account(mut)]
+    pub treasury: Option<Account<'info, CommunityTreasury>>,
+    
+    /// CHECK: Address of the community's native mint treasury, only read to check whether it exists
+    #[account(
+        seeds = [b"treasury".as_ref(), community.key().as_ref(), anchor_spl::token::spl_token::native_mint::ID.as_ref()],
+        bump
+    )]
+    pub native_treasury: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, anchor_spl::token::TokenAccount>>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String, description: String, investment_strategy: String)]
+pub struct CreateFaction<'info> {
+    #[account(
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump,
+        constraint = governance.is_active
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 4 + name.len() + 4 + description.len() + 32 + 8 + 8 + 4 + investment_strategy.len() + 1 + 8 + 8,
+        seeds = [b"faction".as_ref(), governance.key().as_ref(), name.as_bytes()],
+        bump
+    )]
+    pub faction: Account<'info, StrategicFaction>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 1 + 1 + 8,
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, FactionMembership>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinFaction<'info> {
+    #[account(
+        mut,
+        constraint = faction.is_active
+    )]
+    pub faction: Account<'info, StrategicFaction>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 8 + 1 + 1 + 8,
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, FactionMembership>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveFaction<'info> {
+    #[account(mut)]
+    pub faction: Account<'info, StrategicFaction>,
+    
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, FactionMembership>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageFactionMember<'info> {
+    #[account(mut)]
+    pub faction: Account<'info, StrategicFaction>,
+    
+    #[account(
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = admin_membership.is_active
+    )]
+    pub admin_membership: Account<'info, FactionMembership>,
+    
+    #[account(
+        mut,
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), member_membership.user.as_ref()],
+        bump,
+        constraint = member_membership.is_active,
+        constraint = member_membership.user != authority.key()
+    )]
+    pub member_membership: Account<'info, FactionMembership>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DelegateToFaction<'info> {
+    #[account(
+        constraint = faction.is_active
+    )]
+    pub faction: Account<'info, StrategicFaction>,
+    
+    #[account(
+        mut,
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = faction_membership.is_active
+    )]
+    pub faction_membership: Account<'info, FactionMembership>,
+    
+    #[account(
+        constraint = community.governance == faction.governance,
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        mut,
+        seeds = [b"membership".as_ref(), community.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 4 + INITIAL_POWER_CHECKPOINTS * POWER_CHECKPOINT_SIZE,
+        seeds = [b"power_checkpoints".as_ref(), community.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub power_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 8 + 8 + 8,
+        seeds = [b"faction_bloc".as_ref(), faction.key().as_ref(), community.key().as_ref()],
+        bump
+    )]
+    pub bloc: Account<'info, FactionBloc>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 4 + INITIAL_POWER_CHECKPOINTS * POWER_CHECKPOINT_SIZE,
+        seeds = [b"bloc_checkpoints".as_ref(), bloc.key().as_ref()],
+        bump
+    )]
+    pub bloc_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 32 + 32 + 32 + 8 + 8,
+        seeds = [b"faction_delegation".as_ref(), faction.key().as_ref(), community.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, FactionDelegation>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UndelegateFromFaction<'info> {
+    pub faction: Account<'info, StrategicFaction>,
+    
+    #[account(
+        mut,
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub faction_membership: Account<'info, FactionMembership>,
+    
+    #[account(
+        mut,
+        seeds = [b"membership".as_ref(), delegation.community.as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 4 + INITIAL_POWER_CHECKPOINTS * POWER_CHECKPOINT_SIZE,
+        seeds = [b"power_checkpoints".as_ref(), delegation.community.as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub power_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        mut,
+        seeds = [b"faction_bloc".as_ref(), faction.key().as_ref(), delegation.community.as_ref()],
+        bump
+    )]
+    pub bloc: Account<'info, FactionBloc>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 4 + INITIAL_POWER_CHECKPOINTS * POWER_CHECKPOINT_SIZE,
+        seeds = [b"bloc_checkpoints".as_ref(), bloc.key().as_ref()],
+        bump
+    )]
+    pub bloc_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"faction_delegation".as_ref(), faction.key().as_ref(), delegation.community.as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, FactionDelegation>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseFactionDelegation<'info> {
+    pub faction: Account<'info, StrategicFaction>,
+    
+    // Only delegations of members removed from the faction can be released by others
+    #[account(
+        mut,
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), delegation.user.as_ref()],
+        bump,
+        constraint = !faction_membership.is_active
+    )]
+    pub faction_membership: Account<'info, FactionMembership>,
+    
+    #[account(
+        mut,
+        seeds = [b"membership".as_ref(), delegation.community.as_ref(), delegation.user.as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    #[account(
+        mut,
+        seeds = [b"power_checkpoints".as_ref(), delegation.community.as_ref(), delegation.user.as_ref()],
+        bump
+    )]
+    pub power_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        mut,
+        seeds = [b"faction_bloc".as_ref(), faction.key().as_ref(), delegation.community.as_ref()],
+        bump
+    )]
+    pub bloc: Account<'info, FactionBloc>,
+    
+    #[account(
+        mut,
+        seeds = [b"bloc_checkpoints".as_ref(), bloc.key().as_ref()],
+        bump
+    )]
+    pub bloc_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"faction_delegation".as_ref(), faction.key().as_ref(), delegation.community.as_ref(), delegation.user.as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, FactionDelegation>,
+    
+    /// CHECK: Owner of the delegation, receives the closed account's rent
+    #[account(mut, address = delegation.user)]
+    pub user: UncheckedAccount<'info>,
+    
+    // Pays for checkpoint growth
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastFactionMemberVote<'info> {
+    pub faction: Account<'info, StrategicFaction>,
+    
+    #[account(
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = faction_membership.is_active
+    )]
+    pub faction_membership: Account<'info, FactionMembership>,
+    
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        seeds = [b"faction_delegation".as_ref(), faction.key().as_ref(), proposal.community.as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, FactionDelegation>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 2 + 8,
+        seeds = [b"faction_vote".as_ref(), faction.key().as_ref(), proposal.key().as_ref()],
+        bump
+    )]
+    pub faction_vote: Account<'info, FactionVote>,
+    
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 32 + 32 + 1 + 8 + 8,
+        seeds = [b"faction_member_vote".as_ref(), faction_vote.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub member_vote: Account<'info, FactionMemberVote>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastFactionVote<'info> {
+    pub faction: Account<'info, StrategicFaction>,
+    
+    #[account(
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = admin_membership.is_active
+    )]
+    pub admin_membership: Account<'info, FactionMembership>,
+    
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [b"faction_bloc".as_ref(), faction.key().as_ref(), proposal.community.as_ref()],
+        bump
+    )]
+    pub bloc: Account<'info, FactionBloc>,
+    
+    #[account(
+        seeds = [b"bloc_checkpoints".as_ref(), bloc.key().as_ref()],
+        bump
+    )]
+    pub bloc_checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    #[account(
+        mut,
+        seeds = [b"faction_vote".as_ref(), faction.key().as_ref(), proposal.key().as_ref()],
+        bump
+    )]
+    pub faction_vote: Account<'info, FactionVote>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateFactionTreasury<'info> {
+    #[account(
+        constraint = faction.is_active
+    )]
+    pub faction: Account<'info, StrategicFaction>,
+    
+    #[account(
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, FactionMembership>,
+    
+    pub mint: Account<'info, anchor_spl::token::Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 32 + 8 + 8 + 1 + 8,
+        seeds = [b"faction_treasury".as_ref(), faction.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, FactionTreasury>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint,
+        token::authority = treasury,
+        seeds = [b"faction_treasury_token".as_ref(), treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct ContributeToFactionTreasury<'info> {
+    pub faction: Account<'info, StrategicFaction>,
+    
+    #[account(
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, FactionMembership>,
+    
+    #[account(
+        mut,
+        seeds = [b"faction_treasury".as_ref(), faction.key().as_ref(), treasury.mint.as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, FactionTreasury>,
+    
+    #[account(
+        mut,
+        constraint = treasury_token_account.key() == treasury.token_account
+    )]
+    pub treasury_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = contributor_token_account.mint == treasury.mint,
+        constraint = contributor_token_account.owner == authority.key()
+    )]
+    pub contributor_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct ProposeFactionSpend<'info> {
+    pub faction: Account<'info, StrategicFaction>,
+    
+    #[account(
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, FactionMembership>,
+    
+    #[account(
         mut,
-        constraint = community.is_active
+        seeds = [b"faction_treasury".as_ref(), faction.key().as_ref(), treasury.mint.as_ref()],
+        bump = treasury.bump
     )]
-    pub community: Account<'info, Community>,
+    pub treasury: Account<'info, FactionTreasury>,
     
     #[account(
-        init,
-        payer = user,
-        space = 8 + 32 + 32 + 4 + 10 + 8 + 8 + 8 + 1, // "member".len() = 6
-        seeds = [b"membership".as_ref(), community.key().as_ref(), user.key().as_ref()],
-        bump
+        constraint = destination_token_account.mint == treasury.mint
     )]
-    pub membership: Account<'info, Membership>,
+    pub destination_token_account: Account<'info, anchor_spl::token::TokenAccount>,
     
     #[account(
         init,
-        payer = user,
-        space = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8,
-        seeds = [b"reputation".as_ref(), community.governance.as_ref(), user.key().as_ref()],
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 8 + 4 + MAX_FACTION_ADMINS * 32 + 8,
+        seeds = [b"faction_spend".as_ref(), treasury.key().as_ref(), treasury.spend_count.to_le_bytes().as_ref()],
         bump
     )]
-    pub reputation: Account<'info, ReputationScore>,
+    pub spend: Account<'info, FactionSpend>,
     
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub authority: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(title: String, description: String, is_investment_proposal: bool, target_collection: Option<Pubkey>, investment_amount: Option<u64>)]
-pub struct CreateProposal<'info> {
+pub struct ApproveFactionSpend<'info> {
+    pub faction: Account<'info, StrategicFaction>,
+    
     #[account(
-        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), authority.key().as_ref()],
         bump,
-        constraint = governance.is_active
+        constraint = membership.is_active
     )]
-    pub governance: Account<'info, GovernanceConfig>,
+    pub membership: Account<'info, FactionMembership>,
+    
+    #[account(
+        seeds = [b"faction_treasury".as_ref(), faction.key().as_ref(), treasury.mint.as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, FactionTreasury>,
     
     #[account(
         mut,
-        constraint = community.governance == governance.key(),
+        constraint = spend.treasury == treasury.key()
+    )]
+    pub spend: Account<'info, FactionSpend>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SpendFactionTreasury<'info> {
+    pub faction: Account<'info, StrategicFaction>,
+    
+    #[account(
+        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, FactionMembership>,
+    
+    #[account(
+        mut,
+        seeds = [b"faction_treasury".as_ref(), faction.key().as_ref(), treasury.mint.as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, FactionTreasury>,
+    
+    // Closed once paid out, its rent goes to whoever pays it out
+    #[account(
+        mut,
+        close = authority,
+        constraint = spend.treasury == treasury.key()
+    )]
+    pub spend: Account<'info, FactionSpend>,
+    
+    #[account(
+        mut,
+        constraint = treasury_token_account.key() == treasury.token_account
+    )]
+    pub treasury_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = destination_token_account.key() == spend.destination
+    )]
+    pub destination_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String, description: String, risk_level: u8, target_collections: Vec<Pubkey>, allocation_percentages: Vec<u8>)]
+pub struct CreateInvestmentStrategy<'info> {
+    #[account(
         constraint = community.is_active
     )]
     pub community: Account<'info, Community>,
@@ -524,12 +6820,12 @@ pub struct CreateProposal<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + 32 + 32 + 32 + 4 + title.len() + 4 + description.len() + 
-            1 + 8 + 8 + 1 + 8 + 8 + 8 + 9 + 1 + 33 + 9,
-        seeds = [b"proposal".as_ref(), community.key().as_ref(), community.proposal_count.to_le_bytes().as_ref()],
+        space = 8 + 32 + 4 + name.len() + 4 + description.len() + 32 + 1 + 
+                4 + (target_collections.len() * 32) + 4 + allocation_percentages.len() + 8 + 8 + 8 + 1,
+        seeds = [b"investment_strategy".as_ref(), community.key().as_ref(), name.as_bytes()],
         bump
     )]
-    pub proposal: Account<'info, Proposal>,
+    pub strategy: Account<'info, InvestmentStrategy>,
     
     #[account(
         mut,
@@ -538,69 +6834,172 @@ pub struct CreateProposal<'info> {
     )]
     pub reputation: Account<'info, ReputationScore>,
     
+    #[account(
+        mut,
+        seeds = [b"community_reputation".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub community_reputation: Account<'info, CommunityReputation>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+} 
+
+#[derive(Accounts)]
+pub struct CreateStrategyPortfolio<'info> {
+    #[account(
+        constraint = strategy.is_active
+    )]
+    pub strategy: Account<'info, InvestmentStrategy>,
+    
+    #[account(
+        constraint = membership.community == strategy.community,
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
+    pub base_mint: Account<'info, anchor_spl::token::Mint>,
+    
     #[account(
         init,
         payer = authority,
-        space = 8 + 32 + 32 + 4 + 10 + 8 + 8 + 1,
-        seeds = [b"activity_reward".as_ref(), authority.key().as_ref(), governance.key().as_ref(), "proposal".as_bytes()],
+        space = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 4 + (strategy.target_collections.len() * (32 + 32 + 8)) + 8 + 8 + 8 + 1,
+        seeds = [b"strategy_portfolio".as_ref(), strategy.key().as_ref()],
         bump
     )]
-    pub activity_reward: Account<'info, ActivityReward>,
+    pub portfolio: Account<'info, StrategyPortfolio>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = base_mint,
+        token::authority = portfolio,
+        seeds = [b"portfolio_token".as_ref(), portfolio.key().as_ref()],
+        bump
+    )]
+    pub portfolio_token_account: Account<'info, anchor_spl::token::TokenAccount>,
     
     #[account(mut)]
     pub authority: Signer<'info>,
     
     pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-#[instruction(vote_type: VoteType)]
-pub struct CastVote<'info> {
-    #[account(
-        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
-        bump,
-        constraint = governance.is_active
-    )]
-    pub governance: Account<'info, GovernanceConfig>,
+#[instruction(collection: Pubkey)]
+pub struct SetHoldingAsset<'info> {
+    pub strategy: Account<'info, InvestmentStrategy>,
     
     #[account(
         mut,
-        constraint = proposal.governance == governance.key(),
-        constraint = proposal.status == ProposalStatus::Active
+        seeds = [b"strategy_portfolio".as_ref(), strategy.key().as_ref()],
+        bump = portfolio.bump
     )]
-    pub proposal: Account<'info, Proposal>,
+    pub portfolio: Account<'info, StrategyPortfolio>,
     
     #[account(
-        constraint = membership.community == proposal.community,
+        constraint = membership.community == strategy.community,
         constraint = membership.user == authority.key(),
         constraint = membership.is_active
     )]
     pub membership: Account<'info, Membership>,
     
     #[account(
-        init,
+        constraint = vault.is_active
+    )]
+    pub vault: Account<'info, FractionalVault>,
+    
+    #[account(
+        constraint = asset_mint.key() == vault.token_mint
+    )]
+    pub asset_mint: Account<'info, anchor_spl::token::Mint>,
+    
+    #[account(
+        init_if_needed,
         payer = authority,
-        space = 8 + 32 + 32 + 1 + 8 + 8,
-        seeds = [b"vote".as_ref(), proposal.key().as_ref(), authority.key().as_ref()],
+        token::mint = asset_mint,
+        token::authority = portfolio,
+        seeds = [b"portfolio_asset".as_ref(), portfolio.key().as_ref(), collection.as_ref(), asset_mint.key().as_ref()],
         bump
     )]
-    pub vote: Account<'info, Vote>,
+    pub asset_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateStrategyNav<'info> {
+    #[account(
+        constraint = community.key() == strategy.community
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(mut)]
+    pub strategy: Account<'info, InvestmentStrategy>,
     
     #[account(
         mut,
-        seeds = [b"reputation".as_ref(), governance.key().as_ref(), authority.key().as_ref()],
+        seeds = [b"strategy_portfolio".as_ref(), strategy.key().as_ref()],
+        bump = portfolio.bump
+    )]
+    pub portfolio: Account<'info, StrategyPortfolio>,
+    
+    #[account(
+        mut,
+        seeds = [b"reputation".as_ref(), community.governance.as_ref(), strategy.creator.as_ref()],
         bump
     )]
-    pub reputation: Account<'info, ReputationScore>,
+    pub creator_reputation: Account<'info, ReputationScore>,
     
     #[account(
-        init,
+        mut,
+        seeds = [b"community_reputation".as_ref(), strategy.community.as_ref(), strategy.creator.as_ref()],
+        bump
+    )]
+    pub creator_community_reputation: Account<'info, CommunityReputation>,
+    
+    // Anyone can crank valuations
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ComputeRebalance<'info> {
+    #[account(
+        constraint = strategy.is_active
+    )]
+    pub strategy: Account<'info, InvestmentStrategy>,
+    
+    #[account(
+        seeds = [b"strategy_portfolio".as_ref(), strategy.key().as_ref()],
+        bump = portfolio.bump
+    )]
+    pub portfolio: Account<'info, StrategyPortfolio>,
+    
+    #[account(
+        init_if_needed,
         payer = authority,
-        space = 8 + 32 + 32 + 4 + 6 + 8 + 8 + 1,
-        seeds = [b"activity_reward".as_ref(), authority.key().as_ref(), governance.key().as_ref(), "vote".as_bytes()],
+        space = 8 + 32 + 32 + 32 + 4 + (strategy.target_collections.len() * (32 + 1 + 8 + 8 + 1)) + 8 + 8 + 1 + 1 + 1,
+        seeds = [b"rebalance_plan".as_ref(), portfolio.key().as_ref()],
         bump
     )]
-    pub activity_reward: Account<'info, ActivityReward>,
+    pub rebalance_plan: Account<'info, RebalancePlan>,
+    
+    #[account(
+        constraint = membership.community == strategy.community,
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
     
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -609,142 +7008,1303 @@ pub struct CastVote<'info> {
 }
 
 #[derive(Accounts)]
-pub struct FinalizeProposal<'info> {
+pub struct FillRebalanceOrder<'info> {
     #[account(
-        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
-        bump,
-        constraint = governance.is_active
+        constraint = strategy.creator == authority.key() @ GovernanceError::InsufficientPermission
     )]
-    pub governance: Account<'info, GovernanceConfig>,
+    pub strategy: Account<'info, InvestmentStrategy>,
     
     #[account(
         mut,
-        constraint = proposal.governance == governance.key(),
-        constraint = proposal.status == ProposalStatus::Active
+        seeds = [b"strategy_portfolio".as_ref(), strategy.key().as_ref()],
+        bump = portfolio.bump
     )]
-    pub proposal: Account<'info, Proposal>,
+    pub portfolio: Account<'info, StrategyPortfolio>,
     
     #[account(
         mut,
-        seeds = [b"reputation".as_ref(), governance.key().as_ref(), proposal.proposer.as_ref()],
-        bump
+        constraint = portfolio_token_account.key() == portfolio.cash_account
     )]
-    pub proposer_reputation: Account<'info, ReputationScore>,
+    pub portfolio_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"rebalance_plan".as_ref(), portfolio.key().as_ref()],
+        bump = rebalance_plan.bump
+    )]
+    pub rebalance_plan: Account<'info, RebalancePlan>,
+    
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == authority.key(),
+        constraint = creator_token_account.mint == portfolio.base_mint
+    )]
+    pub creator_token_account: Account<'info, anchor_spl::token::TokenAccount>,
     
+    // Checked against the order's holding by the handler
     #[account(mut)]
+    pub portfolio_asset_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = creator_asset_account.owner == authority.key()
+    )]
+    pub creator_asset_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
     pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct ExecuteProposal<'info> {
+pub struct CancelRebalancePlan<'info> {
+    pub strategy: Account<'info, InvestmentStrategy>,
+    
     #[account(
-        mut,
-        constraint = proposal.status == ProposalStatus::Succeeded
+        seeds = [b"strategy_portfolio".as_ref(), strategy.key().as_ref()],
+        bump = portfolio.bump
     )]
-    pub proposal: Account<'info, Proposal>,
+    pub portfolio: Account<'info, StrategyPortfolio>,
     
     #[account(
         mut,
-        seeds = [b"reputation".as_ref(), proposal.governance.as_ref(), proposal.proposer.as_ref()],
-        bump
+        seeds = [b"rebalance_plan".as_ref(), portfolio.key().as_ref()],
+        bump = rebalance_plan.bump
     )]
-    pub proposer_reputation: Account<'info, ReputationScore>,
+    pub rebalance_plan: Account<'info, RebalancePlan>,
+    
+    #[account(
+        constraint = membership.community == strategy.community,
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
     
-    #[account(mut)]
     pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction(name: String, description: String, investment_strategy: String)]
-pub struct CreateFaction<'info> {
+#[instruction(title: String, description: String, participants: Vec<FederatedParticipantTerms>)]
+pub struct CreateFederatedProposal<'info> {
     #[account(
+        mut,
         seeds = [b"governance".as_ref(), governance.authority.as_ref()],
         bump,
         constraint = governance.is_active
     )]
     pub governance: Account<'info, GovernanceConfig>,
     
+    #[account(
+        constraint = community.governance == governance.key(),
+        constraint = community.is_active
+    )]
+    pub community: Account<'info, Community>,
+    
+    #[account(
+        constraint = membership.community == community.key(),
+        constraint = membership.user == authority.key(),
+        constraint = membership.is_active
+    )]
+    pub membership: Account<'info, Membership>,
+    
     #[account(
         init,
         payer = authority,
-        space = 8 + 32 + 4 + name.len() + 4 + description.len() + 32 + 8 + 8 + 4 + investment_strategy.len() + 1,
-        seeds = [b"faction".as_ref(), governance.key().as_ref(), name.as_bytes()],
+        space = 8 + 32 + 32 + 4 + title.len() + 4 + description.len() + 32 + 32 + 
+                4 + (participants.len() * (32 + 1 + 8 + 1 + 1)) + 1 + 1 + 8 + 8,
+        seeds = [b"federated_proposal".as_ref(), governance.key().as_ref(), governance.proposal_count.to_le_bytes().as_ref()],
         bump
     )]
-    pub faction: Account<'info, StrategicFaction>,
+    pub federated_proposal: Account<'info, FederatedProposal>,
+    
+    pub mint: Account<'info, anchor_spl::token::Mint>,
+    
+    // Receives the contributions, e.g. the seller's payment account in a vault buyout
+    #[account(
+        constraint = destination_token_account.mint == mint.key()
+    )]
+    pub destination_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeFederatedProposal<'info> {
+    #[account(mut)]
+    pub federated_proposal: Account<'info, FederatedProposal>,
+    
+    // Anyone can finalize
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DrawFederatedContribution<'info> {
+    #[account(mut)]
+    pub federated_proposal: Account<'info, FederatedProposal>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury".as_ref(), treasury.community.as_ref(), treasury.mint.as_ref()],
+        bump = treasury.bump,
+        constraint = treasury.mint == federated_proposal.mint @ GovernanceError::InvalidTreasuryAccount
+    )]
+    pub treasury: Account<'info, CommunityTreasury>,
+    
+    #[account(
+        mut,
+        constraint = treasury_token_account.key() == treasury.token_account @ GovernanceError::InvalidTreasuryAccount
+    )]
+    pub treasury_token_account: Account<'info, anchor_spl::token::TokenAccount>,
     
     #[account(
-        init,
-        payer = authority,
-        space = 8 + 32 + 32 + 8 + 1,
-        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), authority.key().as_ref()],
-        bump
+        mut,
+        constraint = destination_token_account.key() == federated_proposal.destination @ GovernanceError::InvalidTreasuryAccount
     )]
-    pub membership: Account<'info, FactionMembership>,
+    pub destination_token_account: Account<'info, anchor_spl::token::TokenAccount>,
     
-    #[account(mut)]
+    // Anyone can draw approved contributions
     pub authority: Signer<'info>,
     
-    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct JoinFaction<'info> {
+pub struct SubmitSignedVotes<'info> {
     #[account(
-        mut,
-        constraint = faction.is_active
+        seeds = [b"governance".as_ref(), governance.authority.as_ref()],
+        bump,
+        constraint = governance.is_active
     )]
-    pub faction: Account<'info, StrategicFaction>,
+    pub governance: Account<'info, GovernanceConfig>,
     
     #[account(
-        init,
-        payer = user,
-        space = 8 + 32 + 32 + 8 + 1,
-        seeds = [b"faction_membership".as_ref(), faction.key().as_ref(), user.key().as_ref()],
-        bump
+        mut,
+        constraint = proposal.governance == governance.key(),
+        constraint = proposal.status == ProposalStatus::Active
     )]
-    pub membership: Account<'info, FactionMembership>,
+    pub proposal: Account<'info, Proposal>,
+    
+    /// CHECK: Instructions sysvar, read to find the ed25519 signature verification
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
     
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub relayer: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(name: String, description: String, risk_level: u8, target_collections: Vec<Pubkey>, allocation_percentages: Vec<u8>)]
-pub struct CreateInvestmentStrategy<'info> {
-    #[account(
-        constraint = community.is_active
-    )]
+pub struct CheckpointVotingPower<'info> {
     pub community: Account<'info, Community>,
     
     #[account(
-        constraint = membership.community == community.key(),
-        constraint = membership.user == authority.key(),
+        seeds = [b"membership".as_ref(), community.key().as_ref(), authority.key().as_ref()],
+        bump,
         constraint = membership.is_active
     )]
     pub membership: Account<'info, Membership>,
     
+    // Optional vote-escrow lock adding decaying voting power
     #[account(
-        init,
-        payer = authority,
-        space = 8 + 32 + 4 + name.len() + 4 + description.len() + 32 + 1 + 
-                4 + (target_collections.len() * 32) + 4 + allocation_percentages.len() + 8 + 8 + 8 + 1,
-        seeds = [b"investment_strategy".as_ref(), community.key().as_ref(), name.as_bytes()],
+        seeds = [b"vote_escrow".as_ref(), community.key().as_ref(), authority.key().as_ref()],
         bump
     )]
-    pub strategy: Account<'info, InvestmentStrategy>,
+    pub vote_escrow: Option<Account<'info, VoteEscrow>>,
     
     #[account(
-        mut,
-        seeds = [b"reputation".as_ref(), community.governance.as_ref(), authority.key().as_ref()],
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 32 + 4 + INITIAL_POWER_CHECKPOINTS * POWER_CHECKPOINT_SIZE,
+        seeds = [b"power_checkpoints".as_ref(), community.key().as_ref(), authority.key().as_ref()],
         bump
     )]
-    pub reputation: Account<'info, ReputationScore>,
+    pub power_checkpoints: Account<'info, VotingPowerCheckpoints>,
     
     #[account(mut)]
     pub authority: Signer<'info>,
     
     pub system_program: Program<'info, System>,
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::entrypoint::{MAX_PERMITTED_DATA_INCREASE, SUCCESS};
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+    use anchor_lang::solana_program::system_program;
+    use std::cell::Cell;
+    use std::sync::Once;
+    
+    thread_local! {
+        static NOW: Cell<i64> = const { Cell::new(0) };
+    }
+    
+    // Serves each test thread its own clock and default rent, cross-program invocations are no-ops
+    struct TestSyscalls;
+    
+    impl SyscallStubs for TestSyscalls {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            let clock = Clock { unix_timestamp: NOW.with(Cell::get), ..Clock::default() };
+            unsafe { (var_addr as *mut Clock).write_unaligned(clock) };
+            SUCCESS
+        }
+        
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe { (var_addr as *mut Rent).write_unaligned(Rent::default()) };
+            SUCCESS
+        }
+    }
+    
+    fn set_time(unix_timestamp: i64) {
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(TestSyscalls));
+        });
+        NOW.with(|now| now.set(unix_timestamp));
+    }
+    
+    // Key preceded by the original data length, as in the runtime's input buffer
+    #[repr(C)]
+    struct KeySlot {
+        original_data_len: u32,
+        key: Pubkey,
+    }
+    
+    // Account info leaked for the rest of the test run, with the length prefix and headroom realloc expects
+    fn account_info(key: Pubkey, owner: Pubkey, is_signer: bool, executable: bool, data: &[u8]) -> &'static AccountInfo<'static> {
+        let slot = Box::leak(Box::new(KeySlot { original_data_len: data.len() as u32, key }));
+        let buffer = vec![0u64; (8 + data.len() + MAX_PERMITTED_DATA_INCREASE) / 8 + 1].leak();
+        let bytes = unsafe { std::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, buffer.len() * 8) };
+        bytes[..8].copy_from_slice(&(data.len() as u64).to_le_bytes());
+        bytes[8..8 + data.len()].copy_from_slice(data);
+        let lamports = Box::leak(Box::new(Rent::default().minimum_balance(data.len())));
+        let owner = Box::leak(Box::new(owner));
+        Box::leak(Box::new(AccountInfo::new(&slot.key, is_signer, true, lamports, &mut bytes[8..8 + data.len()], owner, executable, 0)))
+    }
+    
+    fn program_account<T: AccountSerialize + AccountDeserialize + Owner + Clone>(key: Pubkey, account: &T, space: usize) -> Account<'static, T> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data.resize(data.len().max(space), 0);
+        Account::try_from(account_info(key, crate::ID, false, false, &data)).unwrap()
+    }
+    
+    // Writes the account back and reads it again, as the next instruction would see it
+    fn persisted<T: AccountSerialize + AccountDeserialize + Owner + Clone>(account: &Account<'static, T>) -> Account<'static, T> {
+        account.exit(&crate::ID).unwrap();
+        Account::try_from(Box::leak(Box::new(account.to_account_info()))).unwrap()
+    }
+    
+    fn signer(key: Pubkey) -> Signer<'static> {
+        Signer::try_from(account_info(key, system_program::ID, true, false, &[])).unwrap()
+    }
+    
+    fn system_program() -> Program<'static, System> {
+        Program::try_from(account_info(system_program::ID, Pubkey::default(), false, true, &[])).unwrap()
+    }
+    
+    // Account with every field zeroed, fixtures only set the fields a test reads
+    fn zeroed<T: AccountDeserialize>() -> T {
+        T::try_deserialize_unchecked(&mut &[0u8; 1024][..]).unwrap()
+    }
+    
+    fn conviction_config(decay_bps: u64) -> ConvictionConfig {
+        let mut config: ConvictionConfig = zeroed();
+        config.decay_bps = decay_bps;
+        config.max_ratio_bps = 2_000;
+        config.weight_bps = 100;
+        config.step_seconds = 10;
+        config
+    }
+    
+    fn funding_request(staked_weight: u64, conviction: u64) -> FundingRequest {
+        let mut request: FundingRequest = zeroed();
+        request.staked_weight = staked_weight;
+        request.conviction = conviction;
+        request
+    }
+    
+    fn community(vote_tipping: VoteTipping) -> Community {
+        let mut community: Community = zeroed();
+        community.vote_tipping = vote_tipping;
+        community
+    }
+    
+    // Open binary proposal whose voters held snapshot_voting_power when it started
+    fn binary_proposal(for_votes: u64, against_votes: u64, abstain_votes: u64, snapshot_voting_power: u64) -> Proposal {
+        let mut proposal: Proposal = zeroed();
+        proposal.ballot_type = BallotType::Binary;
+        proposal.voting_mode = VotingMode::Linear;
+        proposal.for_votes = for_votes;
+        proposal.against_votes = against_votes;
+        proposal.abstain_votes = abstain_votes;
+        proposal.snapshot_voting_power = snapshot_voting_power;
+        proposal
+    }
+    
+    fn holding(quantity: u64) -> PortfolioHolding {
+        PortfolioHolding { collection: Pubkey::new_unique(), asset_account: Pubkey::default(), quantity }
+    }
+    
+    // Precompile instruction data with every signature, public key and message embedded in it
+    fn ed25519_instruction(entries: &[(Pubkey, &[u8])], instruction_index: u16) -> Instruction {
+        let header = 2 + entries.len() * 14;
+        let mut offsets = Vec::new();
+        let mut payload = Vec::new();
+        for (public_key, message) in entries {
+            let public_key_offset = header + payload.len();
+            payload.extend_from_slice(public_key.as_ref());
+            let signature_offset = header + payload.len();
+            payload.extend_from_slice(&[0u8; 64]);
+            let message_offset = header + payload.len();
+            payload.extend_from_slice(message);
+            
+            for value in [
+                signature_offset as u16,
+                instruction_index,
+                public_key_offset as u16,
+                instruction_index,
+                message_offset as u16,
+                message.len() as u16,
+                instruction_index,
+            ] {
+                offsets.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        
+        let mut data = vec![entries.len() as u8, 0];
+        data.extend(offsets);
+        data.extend(payload);
+        Instruction { program_id: ed25519_program::ID, accounts: Vec::new(), data }
+    }
+    
+    fn governance(reputation_decay_bps: u16) -> GovernanceConfig {
+        let mut governance: GovernanceConfig = zeroed();
+        governance.reward_epoch_duration = 100;
+        governance.reputation_decay_bps = reputation_decay_bps;
+        governance
+    }
+    
+    fn governance_account() -> Account<'static, GovernanceConfig> {
+        let mut governance: GovernanceConfig = zeroed();
+        governance.is_active = true;
+        governance.reward_epoch_duration = 100;
+        governance.total_communities = 1;
+        program_account(Pubkey::new_unique(), &governance, 0)
+    }
+    
+    // Active open community founded by authority
+    fn community_account(governance: Pubkey, authority: Pubkey) -> Account<'static, Community> {
+        let mut community: Community = zeroed();
+        community.governance = governance;
+        community.authority = authority;
+        community.is_active = true;
+        community.member_count = 1;
+        community.total_voting_power = 100;
+        program_account(Pubkey::new_unique(), &community, 0)
+    }
+    
+    fn membership_account(community: &Account<Community>, user: Pubkey, role: MemberRole, voting_power: u64) -> Account<'static, Membership> {
+        let mut membership: Membership = zeroed();
+        membership.community = community.key();
+        membership.user = user;
+        membership.role = role;
+        membership.voting_power = voting_power;
+        membership.is_active = true;
+        let (key, _) = Pubkey::find_program_address(&[b"membership", community.key().as_ref(), user.as_ref()], &crate::ID);
+        program_account(key, &membership, 0)
+    }
+    
+    // Accounts of a user joining the community on an existing or fresh membership
+    fn join_accounts(
+        governance: &Account<'static, GovernanceConfig>,
+        community: Account<'static, Community>,
+        membership: Account<'static, Membership>,
+        user: Pubkey,
+    ) -> JoinCommunity<'static> {
+        JoinCommunity {
+            governance: governance.clone(),
+            community,
+            membership,
+            power_checkpoints: program_account(Pubkey::new_unique(), &zeroed::<VotingPowerCheckpoints>(), 8 + 32 + 32 + 4 + INITIAL_POWER_CHECKPOINTS * POWER_CHECKPOINT_SIZE),
+            reputation: program_account(Pubkey::new_unique(), &zeroed::<ReputationScore>(), 0),
+            community_reputation: program_account(Pubkey::new_unique(), &zeroed::<CommunityReputation>(), 0),
+            requirement_collection: None,
+            requirement_vault: None,
+            requirement_token_account: None,
+            nft_metadata: None,
+            invite: None,
+            inviter: None,
+            application: None,
+            user: signer(user),
+            system_program: system_program(),
+        }
+    }
+    
+    fn reputation_account(user: Pubkey, participation_score: u64, decayed_at: i64) -> Account<'static, ReputationScore> {
+        let mut reputation: ReputationScore = zeroed();
+        reputation.user = user;
+        reputation.participation_score = participation_score;
+        reputation.total_score = participation_score;
+        reputation.decayed_at = decayed_at;
+        program_account(Pubkey::new_unique(), &reputation, 0)
+    }
+    
+    fn proposal_account(governance: &Account<GovernanceConfig>, community: &Account<Community>, status: ProposalStatus, reveal_end_time: i64) -> Account<'static, Proposal> {
+        let mut proposal: Proposal = zeroed();
+        proposal.governance = governance.key();
+        proposal.community = community.key();
+        proposal.proposer = Pubkey::new_unique();
+        proposal.status = status;
+        proposal.end_time = reveal_end_time;
+        proposal.reveal_end_time = reveal_end_time;
+        program_account(Pubkey::new_unique(), &proposal, 1_000)
+    }
+    
+    // Accounts executing a proposal without stored instructions or a typed action
+    fn execute_accounts(
+        governance: &Account<'static, GovernanceConfig>,
+        community: &Account<'static, Community>,
+        proposal: Account<'static, Proposal>,
+    ) -> ExecuteProposal<'static> {
+        ExecuteProposal {
+            governance: governance.clone(),
+            proposal,
+            community: community.clone(),
+            proposal_instructions: None,
+            proposer_reputation: program_account(Pubkey::new_unique(), &zeroed::<ReputationScore>(), 0),
+            proposer_community_reputation: program_account(Pubkey::new_unique(), &zeroed::<CommunityReputation>(), 0),
+            treasury: None,
+            treasury_token_account: None,
+            destination_token_account: None,
+            vault: None,
+            fraction_treasury: None,
+            fraction_treasury_token_account: None,
+            seller_fraction_account: None,
+            rating_system: None,
+            collection: None,
+            strategy: None,
+            member_membership: None,
+            portfolio: None,
+            target_strategy: None,
+            rebalance_plan: None,
+            federated_proposal: None,
+            config_amendment: None,
+            amendment_approval: None,
+            reward_pool: None,
+            council: None,
+            authority: signer(Pubkey::new_unique()),
+            token_program: None,
+            system_program: None,
+        }
+    }
+    
+    fn veto_accounts(
+        governance: &Account<'static, GovernanceConfig>,
+        community: &Account<'static, Community>,
+        proposal: Account<'static, Proposal>,
+    ) -> VetoProposal<'static> {
+        let moderator = Pubkey::new_unique();
+        VetoProposal {
+            governance: governance.clone(),
+            community: community.clone(),
+            proposal,
+            proposer_reputation: program_account(Pubkey::new_unique(), &zeroed::<ReputationScore>(), 0),
+            proposer_community_reputation: program_account(Pubkey::new_unique(), &zeroed::<CommunityReputation>(), 0),
+            membership: membership_account(community, moderator, MemberRole::Moderator, 10),
+            authority: signer(moderator),
+        }
+    }
+    
+    // Accounts settling a SOL deposit, with or without an initialized native mint treasury
+    fn settle_accounts(
+        community: &Account<'static, Community>,
+        proposal: Account<'static, Proposal>,
+        native_treasury_exists: bool,
+    ) -> SettleProposalDeposit<'static> {
+        let native_treasury_data: &[u8] = if native_treasury_exists { &[0u8; 8] } else { &[] };
+        SettleProposalDeposit {
+            community: community.clone(),
+            proposer: SystemAccount::try_from(account_info(proposal.proposer, system_program::ID, false, false, &[])).unwrap(),
+            proposal,
+            vault_authority: UncheckedAccount::try_from(account_info(Pubkey::new_unique(), system_program::ID, false, false, &[])),
+            deposit_vault: None,
+            proposer_deposit_account: None,
+            treasury: None,
+            native_treasury: UncheckedAccount::try_from(account_info(Pubkey::new_unique(), crate::ID, false, false, native_treasury_data)),
+            treasury_token_account: None,
+            authority: signer(Pubkey::new_unique()),
+            token_program: None,
+        }
+    }
+    
+    fn amendment_account(governance: &Account<GovernanceConfig>, update: GovernanceConfigUpdate, approvals: u32) -> Account<'static, ConfigAmendment> {
+        let mut amendment: ConfigAmendment = zeroed();
+        amendment.governance = governance.key();
+        amendment.config_version = governance.config_version;
+        amendment.update = update;
+        amendment.approvals = approvals;
+        program_account(Pubkey::new_unique(), &amendment, 0)
+    }
+    
+    // Accounts of an admin proposing in a community without proposal deposits
+    fn create_proposal_accounts(
+        governance: &Account<'static, GovernanceConfig>,
+        community: &Account<'static, Community>,
+    ) -> CreateProposal<'static> {
+        let admin = Pubkey::new_unique();
+        CreateProposal {
+            governance: governance.clone(),
+            community: community.clone(),
+            membership: membership_account(community, admin, MemberRole::Admin, 100),
+            proposal: program_account(Pubkey::new_unique(), &zeroed::<Proposal>(), 1_000),
+            reputation: program_account(Pubkey::new_unique(), &zeroed::<ReputationScore>(), 0),
+            community_reputation: program_account(Pubkey::new_unique(), &zeroed::<CommunityReputation>(), 0),
+            activity_reward: program_account(Pubkey::new_unique(), &zeroed::<ActivityReward>(), 0),
+            deposit_mint: None,
+            vault_authority: UncheckedAccount::try_from(account_info(Pubkey::new_unique(), system_program::ID, false, false, &[])),
+            deposit_vault: None,
+            proposer_deposit_account: None,
+            rebalance_plan: None,
+            authority: signer(admin),
+            system_program: system_program(),
+            token_program: None,
+        }
+    }
+    
+    fn proposal_args(action: Option<ProposalAction>) -> CreateProposalArgs {
+        CreateProposalArgs {
+            title: "Proposal".to_string(),
+            description: String::new(),
+            is_investment_proposal: false,
+            target_collection: None,
+            investment_amount: None,
+            action,
+            ballot_type: BallotType::Binary,
+            options: Vec::new(),
+        }
+    }
+    
+    fn faction_account(founder: Pubkey, admin_count: u64) -> Account<'static, StrategicFaction> {
+        let mut faction: StrategicFaction = zeroed();
+        faction.founder = founder;
+        faction.member_count = admin_count;
+        faction.is_active = true;
+        faction.admin_count = admin_count;
+        program_account(Pubkey::new_unique(), &faction, 0)
+    }
+    
+    fn faction_membership_account(faction: &Account<StrategicFaction>, user: Pubkey, role: MemberRole) -> Account<'static, FactionMembership> {
+        let mut membership: FactionMembership = zeroed();
+        membership.faction = faction.key();
+        membership.user = user;
+        membership.role = role;
+        membership.is_active = true;
+        program_account(Pubkey::new_unique(), &membership, 0)
+    }
+    
+    fn token_account(key: Pubkey, mint: Pubkey) -> Account<'static, anchor_spl::token::TokenAccount> {
+        let state = anchor_spl::token::spl_token::state::Account {
+            mint,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut data = vec![0u8; anchor_spl::token::spl_token::state::Account::LEN];
+        anchor_spl::token::spl_token::state::Account::pack(state, &mut data).unwrap();
+        Account::try_from(account_info(key, anchor_spl::token::ID, false, false, &data)).unwrap()
+    }
+    
+    fn token_program() -> Program<'static, Token> {
+        Program::try_from(account_info(anchor_spl::token::ID, Pubkey::default(), false, true, &[])).unwrap()
+    }
+    
+    fn rebalance_plan_account(community: &Account<Community>, computed_at: i64) -> Account<'static, RebalancePlan> {
+        let mut plan: RebalancePlan = zeroed();
+        plan.community = community.key();
+        plan.computed_at = computed_at;
+        program_account(Pubkey::new_unique(), &plan, 8 + 32 + 32 + 32 + 4 + 8 + 8 + 1 + 1 + 1)
+    }
+    
+    // Accounts recomputing the plan of a strategy without holdings
+    fn compute_rebalance_accounts(
+        community: &Account<'static, Community>,
+        plan: Account<'static, RebalancePlan>,
+        user: Pubkey,
+        role: MemberRole,
+    ) -> ComputeRebalance<'static> {
+        let mut strategy: InvestmentStrategy = zeroed();
+        strategy.community = community.key();
+        strategy.creator = Pubkey::new_unique();
+        strategy.is_active = true;
+        ComputeRebalance {
+            strategy: program_account(Pubkey::new_unique(), &strategy, 0),
+            portfolio: program_account(Pubkey::new_unique(), &zeroed::<StrategyPortfolio>(), 0),
+            rebalance_plan: plan,
+            membership: membership_account(community, user, role, 10),
+            authority: signer(user),
+            system_program: system_program(),
+        }
+    }
+    
+    fn mint_account(key: Pubkey) -> Account<'static, anchor_spl::token::Mint> {
+        let state = anchor_spl::token::spl_token::state::Mint { is_initialized: true, ..Default::default() };
+        let mut data = vec![0u8; anchor_spl::token::spl_token::state::Mint::LEN];
+        anchor_spl::token::spl_token::state::Mint::pack(state, &mut data).unwrap();
+        Account::try_from(account_info(key, anchor_spl::token::ID, false, false, &data)).unwrap()
+    }
+    
+    fn create_federated_accounts(
+        governance: &Account<'static, GovernanceConfig>,
+        community: &Account<'static, Community>,
+    ) -> CreateFederatedProposal<'static> {
+        let proposer = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        CreateFederatedProposal {
+            governance: governance.clone(),
+            community: community.clone(),
+            membership: membership_account(community, proposer, MemberRole::Member, 10),
+            federated_proposal: program_account(Pubkey::new_unique(), &zeroed::<FederatedProposal>(), 1_000),
+            mint: mint_account(mint),
+            destination_token_account: token_account(Pubkey::new_unique(), mint),
+            authority: signer(proposer),
+            system_program: system_program(),
+        }
+    }
+    
+    // Remaining accounts listing the given communities
+    fn community_infos(communities: &[&Account<'static, Community>]) -> &'static [AccountInfo<'static>] {
+        Box::leak(communities.iter().map(|community| community.to_account_info()).collect::<Vec<_>>().into_boxed_slice())
+    }
+    
+    #[test]
+    fn accrue_conviction_applies_every_elapsed_step() {
+        let config = conviction_config(5_000);
+        let mut request = funding_request(100, 0);
+        
+        // Part of a step does not accrue
+        accrue_conviction(&mut request, &config, 9).unwrap();
+        assert_eq!((request.conviction, request.last_updated), (0, 0));
+        
+        // y = 0.5 * y + 100 over three steps: 100, 150, 175
+        accrue_conviction(&mut request, &config, 35).unwrap();
+        assert_eq!(request.conviction, 175);
+        assert_eq!(request.last_updated, 30);
+        
+        // Accruing step by step reaches the same conviction
+        let mut stepwise = funding_request(100, 0);
+        for time in [10, 20, 30] {
+            accrue_conviction(&mut stepwise, &config, time).unwrap();
+        }
+        assert_eq!(stepwise.conviction, request.conviction);
+    }
+    
+    #[test]
+    fn accrue_conviction_decays_without_stake() {
+        let config = conviction_config(5_000);
+        let mut request = funding_request(0, 400);
+        accrue_conviction(&mut request, &config, 20).unwrap();
+        assert_eq!(request.conviction, 100);
+    }
+    
+    #[test]
+    fn tipped_outcome_is_open_while_votes_can_change_it() {
+        let proposal = binary_proposal(40, 30, 0, 100);
+        assert_eq!(tipped_outcome(&proposal, &community(VoteTipping::Strict), 50).unwrap(), None);
+        assert_eq!(tipped_outcome(&proposal, &community(VoteTipping::Disabled), 50).unwrap(), None);
+    }
+    
+    #[test]
+    fn tipped_outcome_strict_waits_for_unbeatable_lead() {
+        let community = community(VoteTipping::Strict);
+        assert_eq!(tipped_outcome(&binary_proposal(60, 10, 0, 100), &community, 50).unwrap(), Some(true));
+        // Remaining 30 could still bring Against level
+        assert_eq!(tipped_outcome(&binary_proposal(40, 10, 0, 80), &community, 50).unwrap(), None);
+    }
+    
+    #[test]
+    fn tipped_outcome_early_succeeds_on_quorum_and_lead() {
+        let community = community(VoteTipping::Early);
+        assert_eq!(tipped_outcome(&binary_proposal(40, 20, 0, 100), &community, 50).unwrap(), Some(true));
+        // Quorum not met yet
+        assert_eq!(tipped_outcome(&binary_proposal(30, 10, 0, 100), &community, 50).unwrap(), None);
+    }
+    
+    #[test]
+    fn tipped_outcome_defeats_when_unwinnable() {
+        let community = community(VoteTipping::Strict);
+        // For can no longer overtake Against
+        assert_eq!(tipped_outcome(&binary_proposal(10, 60, 0, 100), &community, 50).unwrap(), Some(false));
+        // Quorum out of reach even if everyone votes
+        assert_eq!(tipped_outcome(&binary_proposal(10, 0, 0, 40), &community, 50).unwrap(), Some(false));
+    }
+    
+    #[test]
+    fn tipped_outcome_ignores_unbounded_tallies() {
+        let community = community(VoteTipping::Early);
+        let mut proposal = binary_proposal(60, 10, 0, 100);
+        proposal.is_secret_ballot = true;
+        assert_eq!(tipped_outcome(&proposal, &community, 50).unwrap(), None);
+        
+        let mut proposal = binary_proposal(60, 10, 0, 100);
+        proposal.voting_mode = VotingMode::Quadratic;
+        assert_eq!(tipped_outcome(&proposal, &community, 50).unwrap(), None);
+        
+        let mut proposal = binary_proposal(60, 10, 0, 100);
+        proposal.reputation_voting = true;
+        assert_eq!(tipped_outcome(&proposal, &community, 50).unwrap(), None);
+    }
+    
+    #[test]
+    fn verified_ed25519_signatures_reads_embedded_signers_and_messages() {
+        let voter = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let message = signed_vote_message(&Pubkey::new_unique(), VoteType::For);
+        let instruction = ed25519_instruction(&[(voter, &message), (other, b"other")], u16::MAX);
+        
+        let signatures = verified_ed25519_signatures(&instruction).unwrap();
+        assert_eq!(signatures, vec![(voter, message), (other, b"other".to_vec())]);
+    }
+    
+    #[test]
+    fn verified_ed25519_signatures_rejects_foreign_data() {
+        let voter = Pubkey::new_unique();
+        let message = signed_vote_message(&Pubkey::new_unique(), VoteType::Against);
+        
+        // Offsets pointing into another instruction
+        assert!(verified_ed25519_signatures(&ed25519_instruction(&[(voter, &message)], 0)).is_err());
+        
+        // Another program
+        let mut instruction = ed25519_instruction(&[(voter, &message)], u16::MAX);
+        instruction.program_id = Pubkey::new_unique();
+        assert!(verified_ed25519_signatures(&instruction).is_err());
+        
+        // Truncated data
+        let mut instruction = ed25519_instruction(&[(voter, &message)], u16::MAX);
+        instruction.data.truncate(instruction.data.len() - 1);
+        assert!(verified_ed25519_signatures(&instruction).is_err());
+        instruction.data.clear();
+        assert!(verified_ed25519_signatures(&instruction).is_err());
+    }
+    
+    #[test]
+    fn portfolio_nav_values_holdings_at_prices_plus_cash() {
+        let holdings = vec![holding(3), holding(5)];
+        assert_eq!(portfolio_nav(&holdings, &[10, 20], 7).unwrap(), 137);
+        assert_eq!(portfolio_nav(&[], &[], 7).unwrap(), 7);
+        assert!(portfolio_nav(&holdings, &[u64::MAX, 0], 0).is_err());
+    }
+    
+    #[test]
+    fn conviction_decay_factor_matches_repeated_multiplication() {
+        assert_eq!(conviction_decay_factor(9_000, 0), CONVICTION_SCALE);
+        assert_eq!(conviction_decay_factor(9_000, 1), CONVICTION_SCALE * 9 / 10);
+        assert_eq!(conviction_decay_factor(9_000, 2), CONVICTION_SCALE * 81 / 100);
+        assert_eq!(conviction_decay_factor(5_000, 10), CONVICTION_SCALE / 1_024);
+    }
+    
+    #[test]
+    fn decayed_participation_decays_per_epoch() {
+        let governance = governance(1_000);
+        assert_eq!(decayed_participation(1_000, &governance, 0, 99), 1_000);
+        assert_eq!(decayed_participation(1_000, &governance, 50, 150), 900);
+        assert_eq!(decayed_participation(1_000, &governance, 0, 250), 810);
+        assert_eq!(decayed_participation(1_000, &governance, 0, (MAX_DECAY_EPOCHS as i64 + 1) * 100), 0);
+    }
+    
+    #[test]
+    fn removed_member_cannot_rejoin() {
+        set_time(1_000);
+        let governance = governance_account();
+        let admin = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let community = community_account(governance.key(), admin);
+        let mut accounts = ManageMember {
+            admin_membership: membership_account(&community, admin, MemberRole::Admin, 100),
+            member_membership: membership_account(&community, user, MemberRole::Member, 10),
+            community,
+            authority: signer(admin),
+        };
+        accounts.community.member_count = 2;
+        accounts.community.total_voting_power = 110;
+        remove_member(Context::new(&crate::ID, &mut accounts, &[], ManageMemberBumps::default())).unwrap();
+        assert!(accounts.member_membership.removed);
+        assert_eq!(accounts.community.member_count, 1);
+        
+        let mut join = join_accounts(&governance, persisted(&accounts.community), persisted(&accounts.member_membership), user);
+        let result = join_community(Context::new(&crate::ID, &mut join, &[], JoinCommunityBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::MemberRemoved));
+        assert!(!join.membership.is_active);
+        assert_eq!(join.community.member_count, 1);
+    }
+    
+    #[test]
+    fn member_deactivated_by_recheck_can_rejoin() {
+        set_time(1_000);
+        let governance = governance_account();
+        let user = Pubkey::new_unique();
+        let community = community_account(governance.key(), Pubkey::new_unique());
+        let mut membership = membership_account(&community, user, MemberRole::Member, 10);
+        membership.is_active = false;
+        
+        let mut join = join_accounts(&governance, community, membership, user);
+        join_community(Context::new(&crate::ID, &mut join, &[], JoinCommunityBumps::default())).unwrap();
+        assert!(join.membership.is_active);
+        assert_eq!(join.community.member_count, 2);
+    }
+    
+    #[test]
+    fn active_member_cannot_join_twice() {
+        set_time(1_000);
+        let governance = governance_account();
+        let user = Pubkey::new_unique();
+        let community = community_account(governance.key(), Pubkey::new_unique());
+        let membership = membership_account(&community, user, MemberRole::Member, 10);
+        
+        let mut join = join_accounts(&governance, community, membership, user);
+        let result = join_community(Context::new(&crate::ID, &mut join, &[], JoinCommunityBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::AlreadyMember));
+    }
+    
+    #[test]
+    fn reputation_requirement_applies_pending_decay() {
+        let mut governance = governance_account();
+        governance.reputation_decay_bps = 5_000;
+        let user = Pubkey::new_unique();
+        let mut community = community_account(governance.key(), Pubkey::new_unique());
+        community.membership_requirement = MembershipRequirement::MinReputation { min_score: 50 };
+        
+        // Within the epoch the score was last decayed in, it still meets the bar
+        set_time(150);
+        let mut join = join_accounts(&governance, community.clone(), program_account(Pubkey::new_unique(), &zeroed::<Membership>(), 0), user);
+        join.reputation = reputation_account(user, 80, 100);
+        join_community(Context::new(&crate::ID, &mut join, &[], JoinCommunityBumps::default())).unwrap();
+        
+        // A single elapsed epoch halves it below the bar
+        set_time(250);
+        let mut join = join_accounts(&governance, community, program_account(Pubkey::new_unique(), &zeroed::<Membership>(), 0), user);
+        join.reputation = reputation_account(user, 80, 100);
+        let result = join_community(Context::new(&crate::ID, &mut join, &[], JoinCommunityBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::MembershipRequirementNotMet));
+        assert_eq!(join.reputation.total_score, 40);
+    }
+    
+    #[test]
+    fn execution_waits_for_the_veto_window() {
+        let mut governance = governance_account();
+        governance.execution_delay = 100;
+        governance.execution_window = 200;
+        let mut community = community_account(governance.key(), Pubkey::new_unique());
+        community.veto_window = 300;
+        
+        // Finalized early at 500 with voting scheduled until 1000, so the veto window runs until 1300
+        let mut proposal = proposal_account(&governance, &community, ProposalStatus::Succeeded, 1_000);
+        proposal.succeeded_at = Some(500);
+        
+        set_time(700);
+        let mut execute = execute_accounts(&governance, &community, proposal.clone());
+        let result = execute_proposal(Context::new(&crate::ID, &mut execute, &[], ExecuteProposalBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::VetoWindowOpen));
+        
+        set_time(1_300);
+        let mut execute = execute_accounts(&governance, &community, proposal.clone());
+        let result = execute_proposal(Context::new(&crate::ID, &mut execute, &[], ExecuteProposalBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::VetoWindowOpen));
+        
+        let mut veto = veto_accounts(&governance, &community, proposal.clone());
+        veto_proposal(Context::new(&crate::ID, &mut veto, &[], VetoProposalBumps::default())).unwrap();
+        assert!(veto.proposal.status == ProposalStatus::Vetoed);
+        
+        // Left unvetoed, it executes once the veto window closed, its execution window opening only then
+        set_time(1_450);
+        let mut execute = execute_accounts(&governance, &community, proposal);
+        execute_proposal(Context::new(&crate::ID, &mut execute, &[], ExecuteProposalBumps::default())).unwrap();
+        assert!(execute.proposal.status == ProposalStatus::Executed);
+    }
+    
+    #[test]
+    fn canceled_proposal_forfeits_its_deposit_once_voted_on() {
+        let governance = governance_account();
+        let community = community_account(governance.key(), Pubkey::new_unique());
+        let mut proposal = proposal_account(&governance, &community, ProposalStatus::Canceled, 1_000);
+        proposal.deposit_amount = 500;
+        set_time(2_000);
+        
+        // Withdrawn before anyone voted, the proposer gets the deposit back
+        let mut settle = settle_accounts(&community, proposal.clone(), false);
+        let proposer_lamports = settle.proposer.to_account_info().lamports();
+        settle_proposal_deposit(Context::new(&crate::ID, &mut settle, &[], SettleProposalDepositBumps::default())).unwrap();
+        assert_eq!(settle.proposer.to_account_info().lamports(), proposer_lamports + 500);
+        assert_eq!(settle.proposal.deposit_amount, 0);
+        
+        // Withdrawn after a vote, it goes to the community instead
+        proposal.deposit_amount = 500;
+        proposal.vote_count = 1;
+        let mut settle = settle_accounts(&community, proposal.clone(), false);
+        let community_lamports = community.to_account_info().lamports();
+        settle_proposal_deposit(Context::new(&crate::ID, &mut settle, &[], SettleProposalDepositBumps::default())).unwrap();
+        assert_eq!(community.to_account_info().lamports(), community_lamports + 500);
+    }
+    
+    #[test]
+    fn forfeited_sol_requires_the_native_treasury_when_it_exists() {
+        let governance = governance_account();
+        let community = community_account(governance.key(), Pubkey::new_unique());
+        let mut proposal = proposal_account(&governance, &community, ProposalStatus::Vetoed, 1_000);
+        proposal.deposit_amount = 500;
+        set_time(2_000);
+        
+        let mut settle = settle_accounts(&community, proposal, true);
+        let community_lamports = community.to_account_info().lamports();
+        let result = settle_proposal_deposit(Context::new(&crate::ID, &mut settle, &[], SettleProposalDepositBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::InvalidDepositAccount));
+        assert_eq!(community.to_account_info().lamports(), community_lamports);
+    }
+    
+    #[test]
+    fn amendment_quorum_counts_only_recognized_communities() {
+        let mut governance = governance_account();
+        governance.total_communities = 10;
+        governance.recognized_communities = 3;
+        governance.voting_period = 100;
+        let mut update: GovernanceConfigUpdate = zeroed::<ConfigAmendment>().update;
+        update.voting_period = Some(50);
+        
+        let mut accounts = UpdateGovernanceConfig {
+            governance: governance.clone(),
+            config_amendment: amendment_account(&governance, update.clone(), 1),
+            authority: signer(Pubkey::new_unique()),
+        };
+        let result = update_governance_config(Context::new(&crate::ID, &mut accounts, &[], UpdateGovernanceConfigBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::AmendmentQuorumNotReached));
+        
+        // Two of three recognized communities are a majority, however many others exist
+        let mut accounts = UpdateGovernanceConfig {
+            governance: governance.clone(),
+            config_amendment: amendment_account(&governance, update, 2),
+            authority: signer(Pubkey::new_unique()),
+        };
+        update_governance_config(Context::new(&crate::ID, &mut accounts, &[], UpdateGovernanceConfigBumps::default())).unwrap();
+        assert_eq!((accounts.governance.voting_period, accounts.governance.config_version), (50, 1));
+    }
+    
+    #[test]
+    fn only_active_communities_can_be_recognized() {
+        let governance = governance_account();
+        let mut community = community_account(governance.key(), Pubkey::new_unique());
+        community.is_active = false;
+        let mut accounts = SetCommunityRecognition {
+            governance: governance.clone(),
+            community: community.clone(),
+            authority: signer(governance.authority),
+        };
+        let result = set_community_recognition(Context::new(&crate::ID, &mut accounts, &[], SetCommunityRecognitionBumps::default()), true);
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::CommunityInactive));
+        
+        accounts.community.is_active = true;
+        set_community_recognition(Context::new(&crate::ID, &mut accounts, &[], SetCommunityRecognitionBumps::default()), true).unwrap();
+        assert!(accounts.community.recognized);
+        assert_eq!((accounts.governance.recognized_communities, accounts.governance.config_version), (1, 0));
+        
+        // Revoking recognition voids outstanding amendments the community may have approved
+        set_community_recognition(Context::new(&crate::ID, &mut accounts, &[], SetCommunityRecognitionBumps::default()), false).unwrap();
+        assert!(!accounts.community.recognized);
+        assert_eq!((accounts.governance.recognized_communities, accounts.governance.config_version), (0, 1));
+    }
+    
+    #[test]
+    fn unrecognized_community_cannot_approve_amendments() {
+        let governance = governance_account();
+        let community = community_account(governance.key(), Pubkey::new_unique());
+        let update: GovernanceConfigUpdate = zeroed::<ConfigAmendment>().update;
+        let mut proposal = proposal_account(&governance, &community, ProposalStatus::Succeeded, 1_000);
+        proposal.action = Some(ProposalAction::UpdateConfig(update.clone()));
+        proposal.succeeded_at = Some(1_000);
+        
+        set_time(1_100);
+        let mut execute = execute_accounts(&governance, &community, proposal);
+        execute.config_amendment = Some(amendment_account(&governance, update, 0));
+        execute.amendment_approval = Some(program_account(Pubkey::new_unique(), &zeroed::<ConfigAmendmentApproval>(), 0));
+        let result = execute_proposal(Context::new(&crate::ID, &mut execute, &[], ExecuteProposalBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::CommunityNotRecognized));
+        assert_eq!(execute.config_amendment.unwrap().approvals, 0);
+    }
+    
+    #[test]
+    fn fund_moving_actions_need_the_council_without_the_investment_flag() {
+        let mut governance = governance_account();
+        governance.voting_period = 100;
+        let mut community = community_account(governance.key(), Pubkey::new_unique());
+        community.council_threshold = 2;
+        set_time(1_000);
+        
+        let transfer = ProposalAction::TreasuryTransfer(TreasuryTransfer {
+            treasury: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            amount: 10,
+        });
+        let mut accounts = create_proposal_accounts(&governance, &community);
+        create_proposal(Context::new(&crate::ID, &mut accounts, &[], CreateProposalBumps::default()), proposal_args(Some(transfer)), 10).unwrap();
+        assert_eq!(accounts.proposal.council_threshold, 2);
+        
+        let removal = ProposalAction::RemoveMember { membership: Pubkey::new_unique() };
+        let mut accounts = create_proposal_accounts(&governance, &community);
+        create_proposal(Context::new(&crate::ID, &mut accounts, &[], CreateProposalBumps::default()), proposal_args(Some(removal)), 10).unwrap();
+        assert_eq!(accounts.proposal.council_threshold, 0);
+    }
+    
+    #[test]
+    fn curator_approvals_are_capped() {
+        let governance = governance_account();
+        let community = community_account(governance.key(), Pubkey::new_unique());
+        let curator = Pubkey::new_unique();
+        let mut council: CuratorCouncil = zeroed();
+        council.community = community.key();
+        council.curators = vec![curator];
+        
+        let mut proposal = proposal_account(&governance, &community, ProposalStatus::Active, 1_000);
+        proposal.council_threshold = 1;
+        proposal.council_approvers = (0..MAX_CURATORS).map(|_| Pubkey::new_unique()).collect();
+        
+        set_time(500);
+        let mut accounts = ApproveAsCurator {
+            council: program_account(Pubkey::new_unique(), &council, 0),
+            proposal,
+            approval: program_account(Pubkey::new_unique(), &zeroed::<CouncilApproval>(), 0),
+            authority: signer(curator),
+            system_program: system_program(),
+        };
+        let result = approve_as_curator(Context::new(&crate::ID, &mut accounts, &[], ApproveAsCuratorBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::TooManyApprovers));
+    }
+    
+    #[test]
+    fn only_the_founder_changes_faction_roles() {
+        let founder = Pubkey::new_unique();
+        let admin = Pubkey::new_unique();
+        let faction = faction_account(founder, 2);
+        let member = Pubkey::new_unique();
+        
+        let mut accounts = ManageFactionMember {
+            faction: faction.clone(),
+            admin_membership: faction_membership_account(&faction, admin, MemberRole::Admin),
+            member_membership: faction_membership_account(&faction, member, MemberRole::Member),
+            authority: signer(admin),
+        };
+        let result = set_faction_role(Context::new(&crate::ID, &mut accounts, &[], ManageFactionMemberBumps::default()), MemberRole::Admin);
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::InsufficientPermission));
+        
+        // Promotions change the admins approving spends
+        accounts.admin_membership = faction_membership_account(&faction, founder, MemberRole::Admin);
+        accounts.authority = signer(founder);
+        set_faction_role(Context::new(&crate::ID, &mut accounts, &[], ManageFactionMemberBumps::default()), MemberRole::Admin).unwrap();
+        assert_eq!((accounts.faction.admin_count, accounts.faction.admin_version), (3, 1));
+    }
+    
+    #[test]
+    fn removed_faction_member_is_only_counted_once() {
+        let founder = Pubkey::new_unique();
+        let faction = faction_account(founder, 1);
+        let member = Pubkey::new_unique();
+        let mut accounts = ManageFactionMember {
+            faction: faction.clone(),
+            admin_membership: faction_membership_account(&faction, founder, MemberRole::Admin),
+            member_membership: faction_membership_account(&faction, member, MemberRole::Member),
+            authority: signer(founder),
+        };
+        accounts.faction.member_count = 2;
+        
+        remove_faction_member(Context::new(&crate::ID, &mut accounts, &[], ManageFactionMemberBumps::default())).unwrap();
+        assert_eq!(accounts.faction.member_count, 1);
+        
+        let result = remove_faction_member(Context::new(&crate::ID, &mut accounts, &[], ManageFactionMemberBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::NotFactionMember));
+        assert_eq!(accounts.faction.member_count, 1);
+    }
+    
+    #[test]
+    fn faction_spend_needs_a_majority_of_the_current_admins() {
+        let founder = Pubkey::new_unique();
+        let admin = Pubkey::new_unique();
+        let faction = faction_account(founder, 3);
+        let mint = Pubkey::new_unique();
+        let mut treasury: FactionTreasury = zeroed();
+        treasury.faction = faction.key();
+        treasury.mint = mint;
+        treasury.token_account = Pubkey::new_unique();
+        let treasury = program_account(Pubkey::new_unique(), &treasury, 0);
+        
+        let mut spend: FactionSpend = zeroed();
+        spend.treasury = treasury.key();
+        spend.destination = Pubkey::new_unique();
+        spend.amount = 100;
+        spend.approvers = vec![founder];
+        let spend = program_account(Pubkey::new_unique(), &spend, 8 + 32 + 32 + 8 + 8 + 4 + MAX_FACTION_ADMINS * 32 + 8);
+        
+        set_time(1_000);
+        let mut accounts = SpendFactionTreasury {
+            faction: faction.clone(),
+            membership: faction_membership_account(&faction, founder, MemberRole::Admin),
+            treasury: treasury.clone(),
+            spend: spend.clone(),
+            treasury_token_account: token_account(treasury.token_account, mint),
+            destination_token_account: token_account(spend.destination, mint),
+            authority: signer(founder),
+            token_program: token_program(),
+        };
+        let result = spend_faction_treasury(Context::new(&crate::ID, &mut accounts, &[], SpendFactionTreasuryBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::FactionSpendNotApproved));
+        
+        let mut approve = ApproveFactionSpend {
+            faction: faction.clone(),
+            membership: faction_membership_account(&faction, admin, MemberRole::Admin),
+            treasury: treasury.clone(),
+            spend: spend.clone(),
+            authority: signer(admin),
+        };
+        approve_faction_spend(Context::new(&crate::ID, &mut approve, &[], ApproveFactionSpendBumps::default())).unwrap();
+        let result = approve_faction_spend(Context::new(&crate::ID, &mut approve, &[], ApproveFactionSpendBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::AlreadyVoted));
+        
+        // Approvals given before the admins changed no longer count
+        accounts.spend = persisted(&approve.spend);
+        accounts.faction.admin_version = 1;
+        let result = spend_faction_treasury(Context::new(&crate::ID, &mut accounts, &[], SpendFactionTreasuryBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::FactionAdminsChanged));
+        
+        accounts.faction.admin_version = 0;
+        spend_faction_treasury(Context::new(&crate::ID, &mut accounts, &[], SpendFactionTreasuryBumps::default())).unwrap();
+        assert_eq!(accounts.treasury.total_spent, 100);
+    }
+    
+    #[test]
+    fn nav_records_only_the_applied_reputation_change() {
+        let governance = governance_account();
+        let community = community_account(governance.key(), Pubkey::new_unique());
+        let mut strategy: InvestmentStrategy = zeroed();
+        strategy.community = community.key();
+        let mut portfolio: StrategyPortfolio = zeroed();
+        portfolio.total_funded = 1_000;
+        portfolio.cash = 500;
+        let mut breakdown: CommunityReputation = zeroed();
+        breakdown.investment_score = 20;
+        let mut reputation: ReputationScore = zeroed();
+        reputation.investment_score = 20;
+        
+        set_time(1_000);
+        let mut accounts = UpdateStrategyNav {
+            community: community.clone(),
+            strategy: program_account(Pubkey::new_unique(), &strategy, 0),
+            portfolio: program_account(Pubkey::new_unique(), &portfolio, 0),
+            creator_reputation: program_account(Pubkey::new_unique(), &reputation, 0),
+            creator_community_reputation: program_account(Pubkey::new_unique(), &breakdown, 0),
+            authority: signer(Pubkey::new_unique()),
+        };
+        
+        // A 50% loss would take 50 points, but the creator only holds 20
+        update_strategy_nav(Context::new(&crate::ID, &mut accounts, &[], UpdateStrategyNavBumps::default())).unwrap();
+        assert_eq!(accounts.portfolio.reputation_credit, -20);
+        assert_eq!(accounts.creator_community_reputation.investment_score, 0);
+        
+        // Recovering to break-even gives back only what was taken
+        accounts.portfolio.cash = 1_000;
+        update_strategy_nav(Context::new(&crate::ID, &mut accounts, &[], UpdateStrategyNavBumps::default())).unwrap();
+        assert_eq!(accounts.portfolio.reputation_credit, 0);
+        assert_eq!(accounts.creator_community_reputation.investment_score, 20);
+    }
+    
+    #[test]
+    fn proposed_rebalance_plan_is_frozen_until_it_expires() {
+        let mut governance = governance_account();
+        governance.voting_period = 100;
+        let community = community_account(governance.key(), Pubkey::new_unique());
+        let plan = rebalance_plan_account(&community, 900);
+        set_time(1_000);
+        
+        let action = ProposalAction::ApproveRebalance { plan: plan.key(), computed_at: 900 };
+        let mut accounts = create_proposal_accounts(&governance, &community);
+        accounts.rebalance_plan = Some(plan.clone());
+        create_proposal(Context::new(&crate::ID, &mut accounts, &[], CreateProposalBumps::default()), proposal_args(Some(action)), 10).unwrap();
+        assert!(accounts.rebalance_plan.as_ref().unwrap().frozen);
+        
+        // Plain members cannot reprice plans, the strategy creator and admins can once the plan expired
+        let plan = persisted(accounts.rebalance_plan.as_ref().unwrap());
+        let mut compute = compute_rebalance_accounts(&community, plan.clone(), Pubkey::new_unique(), MemberRole::Member);
+        let result = compute_rebalance(Context::new(&crate::ID, &mut compute, &[], ComputeRebalanceBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::InsufficientPermission));
+        
+        let mut compute = compute_rebalance_accounts(&community, plan.clone(), Pubkey::new_unique(), MemberRole::Admin);
+        let result = compute_rebalance(Context::new(&crate::ID, &mut compute, &[], ComputeRebalanceBumps::default()));
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::RebalancePending));
+        
+        set_time(900 + REBALANCE_PLAN_MAX_AGE + 1);
+        compute_rebalance(Context::new(&crate::ID, &mut compute, &[], ComputeRebalanceBumps::default())).unwrap();
+        assert!(!compute.rebalance_plan.frozen);
+        assert_eq!(compute.rebalance_plan.computed_at, 900 + REBALANCE_PLAN_MAX_AGE + 1);
+    }
+    
+    #[test]
+    fn expired_rebalance_plan_cannot_be_filled() {
+        let governance = governance_account();
+        let community = community_account(governance.key(), Pubkey::new_unique());
+        let mut plan = rebalance_plan_account(&community, 1_000);
+        plan.approved = true;
+        let mint = Pubkey::new_unique();
+        
+        set_time(1_000 + REBALANCE_PLAN_MAX_AGE + 1);
+        let mut accounts = FillRebalanceOrder {
+            strategy: program_account(Pubkey::new_unique(), &zeroed::<InvestmentStrategy>(), 0),
+            portfolio: program_account(Pubkey::new_unique(), &zeroed::<StrategyPortfolio>(), 0),
+            portfolio_token_account: token_account(Pubkey::new_unique(), mint),
+            rebalance_plan: plan,
+            creator_token_account: token_account(Pubkey::new_unique(), mint),
+            portfolio_asset_account: token_account(Pubkey::new_unique(), mint),
+            creator_asset_account: token_account(Pubkey::new_unique(), mint),
+            authority: signer(Pubkey::new_unique()),
+            token_program: token_program(),
+        };
+        let result = fill_rebalance_order(Context::new(&crate::ID, &mut accounts, &[], FillRebalanceOrderBumps::default()), 0);
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::RebalancePlanExpired));
+    }
+    
+    #[test]
+    fn federated_participants_are_active_communities_of_the_governance() {
+        let governance = governance_account();
+        let community = community_account(governance.key(), Pubkey::new_unique());
+        let partner = community_account(governance.key(), Pubkey::new_unique());
+        let outsider = community_account(Pubkey::new_unique(), Pubkey::new_unique());
+        set_time(1_000);
+        
+        let terms = |communities: &[&Account<Community>]| -> Vec<FederatedParticipantTerms> {
+            communities.iter()
+                .map(|community| FederatedParticipantTerms { community: community.key(), required: false, contribution: 10 })
+                .collect()
+        };
+        
+        let mut accounts = create_federated_accounts(&governance, &community);
+        let result = create_federated_proposal(
+            Context::new(&crate::ID, &mut accounts, community_infos(&[&community, &outsider]), CreateFederatedProposalBumps::default()),
+            "Buyout".to_string(), String::new(), terms(&[&community, &outsider]), 2, 100,
+        );
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::InvalidFederation));
+        
+        let mut inactive = community_account(governance.key(), Pubkey::new_unique());
+        inactive.is_active = false;
+        let inactive = persisted(&inactive);
+        let result = create_federated_proposal(
+            Context::new(&crate::ID, &mut accounts, community_infos(&[&community, &inactive]), CreateFederatedProposalBumps::default()),
+            "Buyout".to_string(), String::new(), terms(&[&community, &inactive]), 2, 100,
+        );
+        assert_eq!(result.unwrap_err(), Error::from(GovernanceError::InvalidFederation));
+        
+        create_federated_proposal(
+            Context::new(&crate::ID, &mut accounts, community_infos(&[&community, &partner]), CreateFederatedProposalBumps::default()),
+            "Buyout".to_string(), String::new(), terms(&[&community, &partner]), 2, 100,
+        ).unwrap();
+        assert_eq!(accounts.federated_proposal.participants.len(), 2);
+    }
+}
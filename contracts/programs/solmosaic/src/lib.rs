@@ -6,13 +6,17 @@ use std::mem::size_of;
 pub mod governance;
 pub mod governance_processor;
 
+// Account contexts and instruction argument types must be reachable from the crate root for the program macro,
+// handlers sharing a name with the program's instructions stay reachable through their module
+pub use governance::*;
+#[allow(ambiguous_glob_reexports)]
+pub use governance_processor::*;
+
 declare_id!("FracSoLmosAicfr6ZGQXpMMbQFAmSoZH5QzJKvn8uh1N");
 
 #[program]
 pub mod solmosaic {
     use super::*;
-    use crate::governance::*;
-    use crate::governance_processor::*;
 
     // Initialize a new fractional share vault for an NFT
     pub fn initialize_vault(
@@ -282,13 +286,13 @@ pub mod solmosaic {
         let collateral_return_ix = anchor_spl::token::Transfer {
             from: ctx.accounts.collateral_account.to_account_info(),
             to: ctx.accounts.user_nft_account.to_account_info(),
-            authority: ctx.accounts.loan.to_account_info(),
+            authority: loan.to_account_info(),
         };
         
         let loan_seeds = &[
             b"loan".as_ref(),
-            ctx.accounts.loan.borrower.as_ref(),
-            ctx.accounts.loan.collateral_mint.as_ref(),
+            loan.borrower.as_ref(),
+            loan.collateral_mint.as_ref(),
             &[ctx.bumps.loan],
         ];
         
@@ -389,54 +393,283 @@ pub mod solmosaic {
         Ok(())
     }
 
+    // Publish a collection price used to value strategy portfolios
+    pub fn update_collection_price(ctx: Context<UpdateCollectionPrice>, price: u64) -> Result<()> {
+        // Only authority can publish prices
+        if ctx.accounts.authority.key() != ctx.accounts.rating_system.authority {
+            return Err(ErrorCode::UnauthorizedRatingUpdate.into());
+        }
+        
+        let price_feed = &mut ctx.accounts.price_feed;
+        price_feed.collection = ctx.accounts.collection.key();
+        price_feed.base_mint = ctx.accounts.base_mint.key();
+        price_feed.price = price;
+        price_feed.updated_at = Clock::get()?.unix_timestamp;
+        
+        Ok(())
+    }
+
     // Governance module functions
     pub fn initialize_governance(
         ctx: Context<InitializeGovernance>,
         name: String,
         min_vote_threshold: u64,
         voting_period: i64,
+        execution_delay: i64,
+        execution_window: i64,
+        reward_epoch_duration: i64,
     ) -> Result<()> {
-        governance_processor::initialize_governance(ctx, name, min_vote_threshold, voting_period)
+        governance_processor::initialize_governance(ctx, name, min_vote_threshold, voting_period, execution_delay, execution_window, reward_epoch_duration)
+    }
+
+    pub fn update_governance_config(ctx: Context<UpdateGovernanceConfig>) -> Result<()> {
+        governance_processor::update_governance_config(ctx)
+    }
+
+    pub fn set_community_recognition(ctx: Context<SetCommunityRecognition>, recognized: bool) -> Result<()> {
+        governance_processor::set_community_recognition(ctx, recognized)
+    }
+
+    pub fn refresh_reputation(ctx: Context<RefreshReputation>) -> Result<()> {
+        governance_processor::refresh_reputation(ctx)
+    }
+
+    pub fn register_reputation(ctx: Context<RegisterReputation>) -> Result<()> {
+        governance_processor::register_reputation(ctx)
+    }
+
+    pub fn get_reputation(ctx: Context<GetReputation>) -> Result<ReputationView> {
+        governance_processor::get_reputation(ctx)
     }
 
     pub fn create_community(
         ctx: Context<CreateCommunity>,
         name: String,
         description: String,
+        veto_window: i64,
+        voting_mode: VotingMode,
+        reveal_period: i64,
+        vote_tipping: VoteTipping,
     ) -> Result<()> {
-        governance_processor::create_community(ctx, name, description)
+        governance_processor::create_community(ctx, name, description, veto_window, voting_mode, reveal_period, vote_tipping)
     }
 
     pub fn join_community(ctx: Context<JoinCommunity>) -> Result<()> {
         governance_processor::join_community(ctx)
     }
 
+    pub fn set_join_policy(ctx: Context<UpdateMembershipPolicy>, join_policy: JoinPolicy) -> Result<()> {
+        governance_processor::set_join_policy(ctx, join_policy)
+    }
+
+    pub fn issue_invite(ctx: Context<IssueInvite>, invitee: Pubkey, expires_at: i64) -> Result<()> {
+        governance_processor::issue_invite(ctx, invitee, expires_at)
+    }
+
+    pub fn revoke_invite(ctx: Context<RevokeInvite>) -> Result<()> {
+        governance_processor::revoke_invite(ctx)
+    }
+
+    pub fn apply_to_community(ctx: Context<ApplyToCommunity>, message: String) -> Result<()> {
+        governance_processor::apply_to_community(ctx, message)
+    }
+
+    pub fn review_application(ctx: Context<ReviewApplication>, approve: bool) -> Result<()> {
+        governance_processor::review_application(ctx, approve)
+    }
+
+    pub fn withdraw_application(ctx: Context<WithdrawApplication>) -> Result<()> {
+        governance_processor::withdraw_application(ctx)
+    }
+
+    pub fn set_membership_requirement(
+        ctx: Context<UpdateMembershipPolicy>,
+        requirement: MembershipRequirement,
+    ) -> Result<()> {
+        governance_processor::set_membership_requirement(ctx, requirement)
+    }
+
+    pub fn recheck_membership(ctx: Context<RecheckMembership>) -> Result<()> {
+        governance_processor::recheck_membership(ctx)
+    }
+
+    pub fn create_voting_vault(ctx: Context<CreateVotingVault>, max_escrow_lock: i64) -> Result<()> {
+        governance_processor::create_voting_vault(ctx, max_escrow_lock)
+    }
+
+    pub fn set_proposal_deposit(
+        ctx: Context<SetProposalDeposit>,
+        amount: u64,
+        deposit_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        governance_processor::set_proposal_deposit(ctx, amount, deposit_mint)
+    }
+
+    pub fn deposit_voting_tokens(ctx: Context<DepositVotingTokens>, amount: u64) -> Result<()> {
+        governance_processor::deposit_voting_tokens(ctx, amount)
+    }
+
+    pub fn withdraw_voting_tokens(ctx: Context<WithdrawVotingTokens>, amount: u64) -> Result<()> {
+        governance_processor::withdraw_voting_tokens(ctx, amount)
+    }
+
+    pub fn lock_escrow_tokens(ctx: Context<LockEscrowTokens>, amount: u64, unlock_time: i64) -> Result<()> {
+        governance_processor::lock_escrow_tokens(ctx, amount, unlock_time)
+    }
+
+    pub fn withdraw_escrow_tokens(ctx: Context<WithdrawEscrowTokens>) -> Result<()> {
+        governance_processor::withdraw_escrow_tokens(ctx)
+    }
+
+    pub fn promote_member(ctx: Context<ManageMember>) -> Result<()> {
+        governance_processor::promote_member(ctx)
+    }
+
+    pub fn demote_member(ctx: Context<ManageMember>) -> Result<()> {
+        governance_processor::demote_member(ctx)
+    }
+
+    pub fn remove_member(ctx: Context<ManageMember>) -> Result<()> {
+        governance_processor::remove_member(ctx)
+    }
+
+    pub fn leave_community(ctx: Context<LeaveCommunity>) -> Result<()> {
+        governance_processor::leave_community(ctx)
+    }
+
     pub fn create_proposal(
         ctx: Context<CreateProposal>,
-        title: String,
-        description: String,
-        is_investment_proposal: bool,
-        target_collection: Option<Pubkey>,
-        investment_amount: Option<u64>,
+        args: CreateProposalArgs,
+        epoch: u64,
     ) -> Result<()> {
-        governance_processor::create_proposal(ctx, title, description, is_investment_proposal, target_collection, investment_amount)
+        governance_processor::create_proposal(ctx, args, epoch)
     }
 
     pub fn cast_vote(
         ctx: Context<CastVote>,
         vote_type: VoteType,
+        epoch: u64,
+    ) -> Result<()> {
+        governance_processor::cast_vote(ctx, vote_type, epoch)
+    }
+
+    pub fn cast_ballot(
+        ctx: Context<CastBallot>,
+        choices: Vec<u8>,
+        epoch: u64,
+    ) -> Result<()> {
+        governance_processor::cast_ballot(ctx, choices, epoch)
+    }
+
+    pub fn commit_vote(ctx: Context<CommitVote>, commitment: [u8; 32]) -> Result<()> {
+        governance_processor::commit_vote(ctx, commitment)
+    }
+
+    pub fn reveal_vote(
+        ctx: Context<RevealVote>,
+        vote_type: VoteType,
+        choices: Vec<u8>,
+        salt: [u8; 32],
+        epoch: u64,
+    ) -> Result<()> {
+        governance_processor::reveal_vote(ctx, vote_type, choices, salt, epoch)
+    }
+
+    pub fn insert_proposal_instructions(
+        ctx: Context<InsertProposalInstructions>,
+        option_index: u8,
+        instructions: Vec<ProposalInstruction>,
     ) -> Result<()> {
-        governance_processor::cast_vote(ctx, vote_type)
+        governance_processor::insert_proposal_instructions(ctx, option_index, instructions)
     }
 
     pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
         governance_processor::finalize_proposal(ctx)
     }
 
-    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+    pub fn execute_proposal<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>) -> Result<()> {
         governance_processor::execute_proposal(ctx)
     }
 
+    pub fn create_treasury(ctx: Context<CreateTreasury>) -> Result<()> {
+        governance_processor::create_treasury(ctx)
+    }
+
+    pub fn contribute_to_treasury(ctx: Context<ContributeToTreasury>, amount: u64) -> Result<()> {
+        governance_processor::contribute_to_treasury(ctx, amount)
+    }
+
+    pub fn get_treasury_balance(ctx: Context<GetTreasuryBalance>) -> Result<u64> {
+        governance_processor::get_treasury_balance(ctx)
+    }
+
+    pub fn initialize_reward_pool(ctx: Context<InitializeRewardPool>, reward_rate: u64) -> Result<()> {
+        governance_processor::initialize_reward_pool(ctx, reward_rate)
+    }
+
+    pub fn claim_rewards(ctx: Context<ClaimRewards>, epoch: u64) -> Result<()> {
+        governance_processor::claim_rewards(ctx, epoch)
+    }
+
+    pub fn initialize_conviction_voting(
+        ctx: Context<InitializeConvictionVoting>,
+        decay_bps: u64,
+        max_ratio_bps: u64,
+        weight_bps: u64,
+        step_seconds: i64,
+        min_effective_weight: u64,
+    ) -> Result<()> {
+        governance_processor::initialize_conviction_voting(ctx, decay_bps, max_ratio_bps, weight_bps, step_seconds, min_effective_weight)
+    }
+
+    pub fn create_funding_request(
+        ctx: Context<CreateFundingRequest>,
+        description: String,
+        requested_amount: u64,
+    ) -> Result<()> {
+        governance_processor::create_funding_request(ctx, description, requested_amount)
+    }
+
+    pub fn stake_conviction(ctx: Context<StakeConviction>, weight: u64) -> Result<()> {
+        governance_processor::stake_conviction(ctx, weight)
+    }
+
+    pub fn unstake_conviction(ctx: Context<UnstakeConviction>, weight: u64) -> Result<()> {
+        governance_processor::unstake_conviction(ctx, weight)
+    }
+
+    pub fn release_conviction_stake(ctx: Context<ReleaseConvictionStake>) -> Result<()> {
+        governance_processor::release_conviction_stake(ctx)
+    }
+
+    pub fn update_conviction(ctx: Context<UpdateConviction>) -> Result<()> {
+        governance_processor::update_conviction(ctx)
+    }
+
+    pub fn cancel_funding_request(ctx: Context<CancelFundingRequest>) -> Result<()> {
+        governance_processor::cancel_funding_request(ctx)
+    }
+
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        governance_processor::cancel_proposal(ctx)
+    }
+
+    pub fn initialize_curator_council(ctx: Context<InitializeCuratorCouncil>) -> Result<()> {
+        governance_processor::initialize_curator_council(ctx)
+    }
+
+    pub fn approve_as_curator(ctx: Context<ApproveAsCurator>) -> Result<()> {
+        governance_processor::approve_as_curator(ctx)
+    }
+
+    pub fn veto_proposal(ctx: Context<VetoProposal>) -> Result<()> {
+        governance_processor::veto_proposal(ctx)
+    }
+
+    pub fn settle_proposal_deposit(ctx: Context<SettleProposalDeposit>) -> Result<()> {
+        governance_processor::settle_proposal_deposit(ctx)
+    }
+
     pub fn create_faction(
         ctx: Context<CreateFaction>,
         name: String,
@@ -450,6 +683,58 @@ pub mod solmosaic {
         governance_processor::join_faction(ctx)
     }
 
+    pub fn leave_faction(ctx: Context<LeaveFaction>) -> Result<()> {
+        governance_processor::leave_faction(ctx)
+    }
+
+    pub fn set_faction_role(ctx: Context<ManageFactionMember>, role: MemberRole) -> Result<()> {
+        governance_processor::set_faction_role(ctx, role)
+    }
+
+    pub fn remove_faction_member(ctx: Context<ManageFactionMember>) -> Result<()> {
+        governance_processor::remove_faction_member(ctx)
+    }
+
+    pub fn delegate_to_faction(ctx: Context<DelegateToFaction>) -> Result<()> {
+        governance_processor::delegate_to_faction(ctx)
+    }
+
+    pub fn undelegate_from_faction(ctx: Context<UndelegateFromFaction>) -> Result<()> {
+        governance_processor::undelegate_from_faction(ctx)
+    }
+
+    pub fn release_faction_delegation(ctx: Context<ReleaseFactionDelegation>) -> Result<()> {
+        governance_processor::release_faction_delegation(ctx)
+    }
+
+    pub fn cast_faction_member_vote(ctx: Context<CastFactionMemberVote>, vote_type: VoteType) -> Result<()> {
+        governance_processor::cast_faction_member_vote(ctx, vote_type)
+    }
+
+    pub fn cast_faction_vote(ctx: Context<CastFactionVote>) -> Result<()> {
+        governance_processor::cast_faction_vote(ctx)
+    }
+
+    pub fn create_faction_treasury(ctx: Context<CreateFactionTreasury>) -> Result<()> {
+        governance_processor::create_faction_treasury(ctx)
+    }
+
+    pub fn contribute_to_faction_treasury(ctx: Context<ContributeToFactionTreasury>, amount: u64) -> Result<()> {
+        governance_processor::contribute_to_faction_treasury(ctx, amount)
+    }
+
+    pub fn propose_faction_spend(ctx: Context<ProposeFactionSpend>, amount: u64) -> Result<()> {
+        governance_processor::propose_faction_spend(ctx, amount)
+    }
+
+    pub fn approve_faction_spend(ctx: Context<ApproveFactionSpend>) -> Result<()> {
+        governance_processor::approve_faction_spend(ctx)
+    }
+
+    pub fn spend_faction_treasury(ctx: Context<SpendFactionTreasury>) -> Result<()> {
+        governance_processor::spend_faction_treasury(ctx)
+    }
+
     pub fn create_investment_strategy(
         ctx: Context<CreateInvestmentStrategy>,
         name: String,
@@ -460,6 +745,60 @@ pub mod solmosaic {
     ) -> Result<()> {
         governance_processor::create_investment_strategy(ctx, name, description, risk_level, target_collections, allocation_percentages)
     }
+
+    pub fn create_strategy_portfolio(ctx: Context<CreateStrategyPortfolio>) -> Result<()> {
+        governance_processor::create_strategy_portfolio(ctx)
+    }
+
+    pub fn set_holding_asset(ctx: Context<SetHoldingAsset>, collection: Pubkey) -> Result<()> {
+        governance_processor::set_holding_asset(ctx, collection)
+    }
+
+    pub fn update_strategy_nav<'info>(ctx: Context<'_, '_, 'info, 'info, UpdateStrategyNav<'info>>) -> Result<()> {
+        governance_processor::update_strategy_nav(ctx)
+    }
+
+    pub fn compute_rebalance<'info>(ctx: Context<'_, '_, 'info, 'info, ComputeRebalance<'info>>) -> Result<()> {
+        governance_processor::compute_rebalance(ctx)
+    }
+
+    pub fn fill_rebalance_order(ctx: Context<FillRebalanceOrder>, order_index: u8) -> Result<()> {
+        governance_processor::fill_rebalance_order(ctx, order_index)
+    }
+
+    pub fn cancel_rebalance_plan(ctx: Context<CancelRebalancePlan>) -> Result<()> {
+        governance_processor::cancel_rebalance_plan(ctx)
+    }
+
+    pub fn create_federated_proposal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateFederatedProposal<'info>>,
+        title: String,
+        description: String,
+        participants: Vec<FederatedParticipantTerms>,
+        min_approvals: u8,
+        duration: i64,
+    ) -> Result<()> {
+        governance_processor::create_federated_proposal(ctx, title, description, participants, min_approvals, duration)
+    }
+
+    pub fn finalize_federated_proposal(ctx: Context<FinalizeFederatedProposal>) -> Result<()> {
+        governance_processor::finalize_federated_proposal(ctx)
+    }
+
+    pub fn draw_federated_contribution(ctx: Context<DrawFederatedContribution>) -> Result<()> {
+        governance_processor::draw_federated_contribution(ctx)
+    }
+
+    pub fn submit_signed_votes<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SubmitSignedVotes<'info>>,
+        votes: Vec<SignedVote>,
+    ) -> Result<()> {
+        governance_processor::submit_signed_votes(ctx, votes)
+    }
+
+    pub fn checkpoint_voting_power(ctx: Context<CheckpointVotingPower>) -> Result<()> {
+        governance_processor::checkpoint_voting_power(ctx)
+    }
 }
 
 #[derive(Accounts)]
@@ -846,6 +1185,39 @@ pub struct VerifyCollection<'info> {
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateCollectionPrice<'info> {
+    #[account(
+        seeds = [b"rating_system".as_ref(), rating_system.authority.as_ref()],
+        bump,
+        constraint = rating_system.is_active
+    )]
+    pub rating_system: Account<'info, RatingSystem>,
+    
+    #[account(
+        seeds = [b"collection".as_ref(), rating_system.key().as_ref(), collection.name.as_bytes()],
+        bump,
+        constraint = collection.rating_system == rating_system.key()
+    )]
+    pub collection: Account<'info, CollectionRating>,
+    
+    pub base_mint: Account<'info, anchor_spl::token::Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + size_of::<CollectionPriceFeed>(),
+        seeds = [b"price_feed".as_ref(), collection.key().as_ref(), base_mint.key().as_ref()],
+        bump
+    )]
+    pub price_feed: Account<'info, CollectionPriceFeed>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 #[account]
 pub struct FractionalVault {
     pub authority: Pubkey,
@@ -909,6 +1281,14 @@ pub struct CollectionRating {
     pub is_verified: bool,
 }
 
+#[account]
+pub struct CollectionPriceFeed {
+    pub collection: Pubkey,
+    pub base_mint: Pubkey,     // Currency the price is quoted in, one feed per collection and currency
+    pub price: u64,            // Price per unit in base mint units
+    pub updated_at: i64,
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("User doesn't have all fractional shares to redeem the NFT")]